@@ -0,0 +1,465 @@
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+use crate::burt::{BurtGang, check_burt_count, check_crossover_rate, check_islands, check_range, check_rate, check_samples_per_eval, check_target, parse_selection};
+use crate::crossover::parse_crossover;
+use crate::fitness::parse_fitness;
+use crate::genome::parse_genome;
+use crate::diversity::parse_sharing;
+use crate::island::{parse_island_setting, parse_migration};
+use crate::sampling::Boundary;
+use crate::mutation::parse_mutation;
+use crate::schedule::parse_schedule;
+use crate::stopping::parse_stop_conditions;
+use crate::headless::OutputFormat;
+use crate::TRAIN_STICKY;
+
+// the values used by -d / --default and for any simulation flag that is left out
+pub const DEFAULT_RANGE: u32 = 100;
+pub const DEFAULT_TARGET: u32 = 7;
+pub const DEFAULT_GENERATIONS: u32 = 150;
+pub const DEFAULT_SURVIVAL_RATE: f32 = 0.25;
+pub const DEFAULT_MUTATION_RATE: f32 = 0.01;
+pub const DEFAULT_BURT_COUNT: u32 = 150;
+
+pub const HELP: &str = "\
+MaLB - watch a gang of Burts learn to guess a number
+
+Usage: malb [OPTIONS]
+
+With no simulation options the parameters are asked for interactively.
+Any simulation option left out uses its default.
+
+Options:
+  -h, --help                  Print this help and exit
+  -d, --default               Use the default value for every simulation option
+      --headless              Train without the TUI and print per-generation stats
+      --format <csv|json>     Output format for --headless [default: csv]
+      --resume <FILE>         Resume training from a saved gang, it keeps its own range, target,
+                              rates, Burts, genome and seed (the other options still apply)
+      --checkpoint-every <N>  Save the gang every N generations [default: 0, off]
+      --checkpoint-file <FILE> Where checkpoints are saved [default: malb_checkpoint.txt]
+      --threads <N>           How many threads train the gang, at least 1 [default: all cores]
+      --report <FILE>         Also write the end of run report to a file (the only place it
+                              goes with --headless)
+
+Simulation options:
+      --range <N>             How large the range is, above 0 [default: 100]
+      --target <N>            The target, between 0 and range [default: 7, or range - 1
+                              if that's lower]
+      --generations <N>       How many generations to train [default: 150]
+      --survival-rate <R>     Survival rate between 0 and 1 [default: 0.25]
+      --mutation-rate <R>     Mutation rate between 0 and 1 [default: 0.01]
+      --burts <N>             How many Burts to use, at least 1 [default: 150]
+      --strategy <S>          Training strategy: normal or sticky [default: normal]
+      --selection <S>         How parents are picked: truncation, tournament[:size],
+                              roulette or rank [default: truncation]
+      --crossover <C>         How two parents are crossed: uniform, blend or blx[:alpha]
+                              [default: blend]
+      --crossover-rate <R>    How often new Burts get two parents, 0 to 1 [default: 0]
+      --mutation <M>          How new Burts are mutated: uniform, gaussian, adaptive[:step]
+                              or gene:probability[:mutation] [default: uniform]
+      --elite <N>             How many of the best Burts of every island are kept untouched
+                              [default: 0]
+      --schedule <S>          How the target moves: fixed, random, cycle:t1,t2,.., drift[:step],
+                              sequence:t1,t2,.. or function:ax+b[:count] to learn a line
+                              (with --genome linear) [default: fixed]
+      --fitness <F>           How guesses are scored: absolute, squared, relative, band[:width]
+                              or samples:k[:fitness] [default: absolute]
+      --samples-per-eval <K>  How many times every Burt is evaluated per generation, it is
+                              ranked on the mean score [default: 1]
+      --genome <G>            What the Burts' genes are: normal, uniform, triangular, lognormal,
+                              mixture or linear [default: normal]
+      --boundary <B>          What happens to guesses outside of the range: clamp, reflect
+                              or truncate (draw again) [default: truncate]
+      --islands <K>           How many islands the Burts are split into, each one is ranked
+                              and bred on its own [default: 1]
+      --migration <M>         How the best Burts move between islands: none, ring[:every[:migrants]]
+                              or random[:every[:migrants]] [default: ring:10:1]
+      --island <I:VAR:VALUE>  Gives island I (from 0) its own survival_rate, mutation_rate or
+                              selection, can be repeated
+      --sharing <S>           Fitness sharing, ranks crowded Burts lower: none or radius[:alpha],
+                              where the niches are grid cells radius wide [default: none]
+      --stop-when <C,..>      Stop training early once any of these is met: perfect:N (best
+                              score 0 for N generations), average:SCORE (average score below
+                              it), stagnant:N (no better average in N generations) or
+                              time:SECONDS (or Nm, Nh) [default: none]
+      --seed <N>              Seed for the random number generator [default: random]
+";
+
+/// how the gang is trained each generation, see `BurtGang::train`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Strategy {
+    Normal,
+    Sticky,
+}
+
+impl Strategy {
+    pub fn is_sticky(&self) -> bool {
+        *self == Strategy::Sticky
+    }
+}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "normal" => Ok(Strategy::Normal),
+            "sticky" => Ok(Strategy::Sticky),
+            _ => Err(String::from("expected normal or sticky")),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Args {
+    pub help: bool,
+    pub default: bool,
+    pub headless: bool,
+    pub format: OutputFormat,
+    pub seed: Option<u64>,
+    pub resume: Option<PathBuf>,
+    pub checkpoint_every: Option<u32>,
+    pub checkpoint_file: Option<PathBuf>,
+    pub threads: Option<usize>,
+    pub report: Option<PathBuf>,
+
+    pub range: Option<u32>,
+    pub target: Option<u32>,
+    pub generations: Option<u32>,
+    pub survival_rate: Option<f32>,
+    pub mutation_rate: Option<f32>,
+    pub burt_count: Option<u32>,
+    pub strategy: Option<Strategy>,
+    pub selection: Option<String>,
+    pub crossover: Option<String>,
+    pub crossover_rate: Option<f32>,
+    pub mutation: Option<String>,
+    pub elite_count: Option<u32>,
+    pub schedule: Option<String>,
+    pub fitness: Option<String>,
+    pub samples_per_eval: Option<u32>,
+    pub genome: Option<String>,
+    pub boundary: Option<Boundary>,
+    pub islands: Option<usize>,
+    pub migration: Option<String>,
+    // (island, variable, value) in the order they were given
+    pub island_settings: Vec<(usize, String, String)>,
+    pub sharing: Option<String>,
+    pub stop_when: Option<String>,
+}
+
+impl Args {
+    /// parses and validates the program arguments, skipping the program name in `args[0]`
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut parsed = Self::default();
+
+        let mut iter = args.iter().skip(1);
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "-d" | "--default" => parsed.default = true,
+                "--headless" => parsed.headless = true,
+                "--format" => parsed.format = value(arg, iter.next())?,
+                "--seed" => parsed.seed = Some(value(arg, iter.next())?),
+                "--resume" => parsed.resume = Some(value(arg, iter.next())?),
+                "--checkpoint-every" => parsed.checkpoint_every = Some(value(arg, iter.next())?),
+                "--checkpoint-file" => parsed.checkpoint_file = Some(value(arg, iter.next())?),
+                "--report" => parsed.report = Some(value(arg, iter.next())?),
+                "--threads" => {
+                    let threads = value(arg, iter.next())?;
+                    if threads == 0 {
+                        return Err(String::from("--threads: There must be at least 1 thread!"));
+                    }
+                    parsed.threads = Some(threads);
+                }
+                "--range" => parsed.range = Some(value(arg, iter.next())?),
+                "--target" => parsed.target = Some(value(arg, iter.next())?),
+                "--generations" => parsed.generations = Some(value(arg, iter.next())?),
+                "--survival-rate" => parsed.survival_rate = Some(value(arg, iter.next())?),
+                "--mutation-rate" => parsed.mutation_rate = Some(value(arg, iter.next())?),
+                "--burts" => parsed.burt_count = Some(value(arg, iter.next())?),
+                "--strategy" => parsed.strategy = Some(value(arg, iter.next())?),
+                "--boundary" => parsed.boundary = Some(value(arg, iter.next())?),
+                "--selection" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_selection(&spec).map_err(|e| format!("--selection: {}", e))?;
+                    parsed.selection = Some(spec);
+                }
+                "--crossover" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_crossover(&spec).map_err(|e| format!("--crossover: {}", e))?;
+                    parsed.crossover = Some(spec);
+                }
+                "--mutation" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_mutation(&spec).map_err(|e| format!("--mutation: {}", e))?;
+                    parsed.mutation = Some(spec);
+                }
+                "--elite" => parsed.elite_count = Some(value(arg, iter.next())?),
+                "--schedule" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_schedule(&spec).map_err(|e| format!("--schedule: {}", e))?;
+                    parsed.schedule = Some(spec);
+                }
+                "--fitness" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_fitness(&spec).map_err(|e| format!("--fitness: {}", e))?;
+                    parsed.fitness = Some(spec);
+                }
+                "--samples-per-eval" => {
+                    let samples = value(arg, iter.next())?;
+                    check_samples_per_eval(samples).map_err(|e| format!("--samples-per-eval: {}", e))?;
+                    parsed.samples_per_eval = Some(samples);
+                }
+                "--genome" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_genome(&spec).map_err(|e| format!("--genome: {}", e))?;
+                    parsed.genome = Some(spec);
+                }
+                "--islands" => {
+                    let islands = value(arg, iter.next())?;
+                    check_islands(islands).map_err(|e| format!("--islands: {}", e))?;
+                    parsed.islands = Some(islands);
+                }
+                "--migration" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_migration(&spec).map_err(|e| format!("--migration: {}", e))?;
+                    parsed.migration = Some(spec);
+                }
+                "--island" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parsed.island_settings.push(parse_island_setting(&spec).map_err(|e| format!("--island: {}", e))?);
+                }
+                "--sharing" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_sharing(&spec).map_err(|e| format!("--sharing: {}", e))?;
+                    parsed.sharing = Some(spec);
+                }
+                "--stop-when" => {
+                    let spec: String = value(arg, iter.next())?;
+                    parse_stop_conditions(&spec).map_err(|e| format!("--stop-when: {}", e))?;
+                    parsed.stop_when = Some(spec);
+                }
+                "--crossover-rate" => {
+                    let rate = value(arg, iter.next())?;
+                    check_crossover_rate(rate).map_err(|e| format!("--crossover-rate: {}", e))?;
+                    parsed.crossover_rate = Some(rate);
+                }
+                _ => return Err(format!("Unknown argument: {}", arg)),
+            }
+        }
+
+        // the same checks the interactive prompts use
+        check_range(parsed.range()).map_err(|e| format!("--range: {}", e))?;
+        // the default target fits any range
+        if let Some(target) = parsed.target {
+            check_target(target, parsed.range()).map_err(|e| format!("--target: {}", e))?;
+        }
+        check_rate("survival rate", parsed.survival_rate()).map_err(|e| format!("--survival-rate: {}", e))?;
+        check_rate("mutation rate", parsed.mutation_rate()).map_err(|e| format!("--mutation-rate: {}", e))?;
+        check_burt_count(parsed.burt_count()).map_err(|e| format!("--burts: {}", e))?;
+        // a resumed gang has its own islands, the settings are checked against them once it's loaded
+        if parsed.resume.is_none() || parsed.islands.is_some() {
+            let islands = parsed.islands.unwrap_or(1);
+            if let Some((island, _, _)) = parsed.island_settings.iter().find(|(island, _, _)| *island >= islands) {
+                return Err(format!("--island: There is no island {} (the islands are 0 to {}, see --islands)", island, islands - 1));
+            }
+        }
+
+        // a resumed gang keeps its own parameters
+        if parsed.resume.is_some() && (parsed.default || parsed.seed.is_some()
+            || parsed.range.is_some() || parsed.target.is_some() || parsed.survival_rate.is_some()
+            || parsed.mutation_rate.is_some() || parsed.burt_count.is_some() || parsed.genome.is_some()) {
+            return Err(String::from("--resume keeps the saved range, target, rates, Burts, genome and seed, \
+                so it can't be combined with --default, --seed, --range, --target, --survival-rate, --mutation-rate, --burts or --genome"));
+        }
+
+        Ok(parsed)
+    }
+
+    /// true if the run can be set up from the arguments alone, without asking the user
+    pub fn non_interactive(&self) -> bool {
+        self.default || self.headless || self.resume.is_some()
+            || self.range.is_some() || self.target.is_some() || self.generations.is_some()
+            || self.survival_rate.is_some() || self.mutation_rate.is_some() || self.burt_count.is_some()
+    }
+
+    /// makes a populated gang from the simulation options
+    pub fn burt_gang(&self, seed: u64, display: bool) -> BurtGang {
+        let mut burt_gang = BurtGang::new(self.range(), self.target(), self.generations(),
+                                          self.survival_rate(), self.mutation_rate(), seed);
+        if let Some(spec) = &self.genome {
+            burt_gang.genome = parse_genome(spec).expect("The genome is checked while parsing");
+        }
+        burt_gang.sticky = self.strategy().is_sticky();
+        burt_gang.populate(self.burt_count(), display);
+        burt_gang
+    }
+
+    /// applies the options that aren't part of making the gang, i.e. to a resumed gang.
+    /// fails if an island setting is for an island the gang doesn't have
+    pub fn configure(&self, burt_gang: &mut BurtGang) -> Result<(), String> {
+        if let Some(strategy) = self.strategy {
+            burt_gang.sticky = strategy.is_sticky();
+        }
+        if let Some(generations) = self.generations {
+            burt_gang.generations = generations;
+        }
+        if let Some(checkpoint_every) = self.checkpoint_every {
+            burt_gang.checkpoint_every = checkpoint_every;
+        }
+        if let Some(path) = &self.checkpoint_file {
+            burt_gang.checkpoint_path = path.clone();
+        }
+        if let Some(spec) = &self.selection {
+            burt_gang.selection = parse_selection(spec).expect("The selection is checked while parsing");
+        }
+        if let Some(spec) = &self.crossover {
+            burt_gang.crossover = parse_crossover(spec).expect("The crossover is checked while parsing");
+        }
+        if let Some(rate) = self.crossover_rate {
+            burt_gang.crossover_rate = rate;
+        }
+        if let Some(spec) = &self.mutation {
+            burt_gang.mutation = parse_mutation(spec).expect("The mutation is checked while parsing");
+        }
+        if let Some(elite_count) = self.elite_count {
+            burt_gang.elite_count = elite_count;
+        }
+        if let Some(spec) = &self.schedule {
+            burt_gang.schedule = parse_schedule(spec).expect("The schedule is checked while parsing");
+        }
+        if let Some(spec) = &self.fitness {
+            burt_gang.fitness = parse_fitness(spec).expect("The fitness is checked while parsing");
+        }
+        if let Some(samples) = self.samples_per_eval {
+            burt_gang.samples_per_eval = samples;
+        }
+        if let Some(boundary) = self.boundary {
+            burt_gang.boundary = boundary;
+        }
+        if let Some(islands) = self.islands {
+            burt_gang.set_islands(islands);
+        }
+        if let Some(spec) = &self.migration {
+            burt_gang.migration = parse_migration(spec).expect("The migration is checked while parsing");
+        }
+        if let Some(spec) = &self.sharing {
+            burt_gang.sharing = parse_sharing(spec).expect("The sharing is checked while parsing");
+        }
+        if let Some(spec) = &self.stop_when {
+            burt_gang.stop_when = parse_stop_conditions(spec).expect("The stop conditions are checked while parsing");
+        }
+        for (island, var, value) in &self.island_settings {
+            burt_gang.set_island(*island, var, value).map_err(|e| format!("--island: {}", e))?;
+        }
+        // only a gang from the interactive prompts has the wrong genome by now, it gets new burts
+        if let Some(spec) = &self.genome {
+            let genome = parse_genome(spec).expect("The genome is checked while parsing");
+            if genome.name() != burt_gang.genome.name() {
+                burt_gang.set_genome(genome);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn range(&self) -> u32 {
+        self.range.unwrap_or(DEFAULT_RANGE)
+    }
+
+    pub fn target(&self) -> u32 {
+        self.target.unwrap_or_else(|| DEFAULT_TARGET.min(self.range().saturating_sub(1)))
+    }
+
+    pub fn generations(&self) -> u32 {
+        self.generations.unwrap_or(DEFAULT_GENERATIONS)
+    }
+
+    pub fn survival_rate(&self) -> f32 {
+        self.survival_rate.unwrap_or(DEFAULT_SURVIVAL_RATE)
+    }
+
+    pub fn mutation_rate(&self) -> f32 {
+        self.mutation_rate.unwrap_or(DEFAULT_MUTATION_RATE)
+    }
+
+    pub fn burt_count(&self) -> u32 {
+        self.burt_count.unwrap_or(DEFAULT_BURT_COUNT)
+    }
+
+    pub fn strategy(&self) -> Strategy {
+        self.strategy.unwrap_or(if TRAIN_STICKY { Strategy::Sticky } else { Strategy::Normal })
+    }
+}
+
+// parses the value following a flag
+fn value<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, String>
+    where T::Err: Display {
+    let value = value.ok_or_else(|| format!("{} expects a value!", flag))?;
+    value.parse::<T>().map_err(|e| format!("Invalid value for {}: {} ({})", flag, value, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+
+    fn parse(args: &str) -> Result<Args, String> {
+        let args: Vec<String> = format!("malb {}", args).split_whitespace().map(String::from).collect();
+        Args::parse(&args)
+    }
+
+    #[test]
+    fn parses_simulation_options() {
+        let args = parse("--range 50 --target 10 --generations 5 --survival-rate 0.5 --mutation-rate 0.1 --burts 20 --strategy sticky --seed 9").unwrap();
+        assert!(args.non_interactive());
+        assert_eq!(args.range(), 50);
+        assert_eq!(args.target(), 10);
+        assert_eq!(args.generations(), 5);
+        assert_eq!(args.survival_rate(), 0.5);
+        assert_eq!(args.mutation_rate(), 0.1);
+        assert_eq!(args.burt_count(), 20);
+        assert_eq!(args.strategy(), Strategy::Sticky);
+        assert_eq!(args.seed, Some(9));
+        assert_eq!(parse("--threads 4").unwrap().threads, Some(4));
+        // a range too small for the default target brings the target down with it
+        assert_eq!(parse("--range 5").unwrap().target(), 4);
+        assert_eq!(parse("").unwrap().target(), DEFAULT_TARGET);
+    }
+
+    #[test]
+    fn rejects_what_the_prompts_reject() {
+        for bad in ["--range 0", "--range 5 --target 5", "--target 100", "--survival-rate 0", "--survival-rate 1",
+                    "--mutation-rate 1.5", "--burts 0", "--strategy fast", "--range", "--nope", "--selection best",
+                    "--crossover swap", "--crossover-rate 2", "--mutation gene:2", "--mutation adaptive:0",
+                    "--resume gang.txt --range 10", "--resume gang.txt --seed 1", "--threads 0",
+                    "--schedule cycle", "--fitness samples:0", "--samples-per-eval 0", "--genome cauchy",
+                    "--resume gang.txt --genome linear", "--boundary wrap",
+                    "--islands 0", "--migration star", "--migration ring:0", "--island 1:mutation_rate:0.1",
+                    "--islands 2 --island 1:survival_rate:2", "--islands 2 --island 0:elite_count:3",
+                    "--sharing 0", "--sharing -2", "--sharing 5:0", "--sharing wide",
+                    "--stop-when perfect:0", "--stop-when average:-1", "--stop-when stagnant", "--stop-when time:5d",
+                    "--stop-when perfect:5,forever:1"] {
+            assert!(parse(bad).is_err(), "{} should be rejected", bad);
+        }
+        assert!(!parse("").unwrap().non_interactive());
+        assert!(parse("--resume gang.txt --generations 500").unwrap().non_interactive());
+    }
+
+    #[test]
+    fn configures_a_resumed_gang() {
+        let mut burt_gang = seeded_gang(2);
+        burt_gang.set_islands(3);
+        let args = parse("--resume gang.txt --strategy sticky --selection rank --island 2:mutation_rate:0.1").unwrap();
+        args.configure(&mut burt_gang).unwrap();
+        assert!(burt_gang.sticky);
+        assert_eq!(burt_gang.selection.name(), "rank");
+        assert_eq!(burt_gang.island_settings()[2].mutation_rate, 0.1);
+
+        // the island is only known to be missing once the gang is loaded
+        let args = parse("--resume gang.txt --island 3:mutation_rate:0.1").unwrap();
+        assert!(args.configure(&mut burt_gang).is_err());
+    }
+}
@@ -1,131 +1,346 @@
 use std::fmt::{Display, Formatter};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
 use better_term::{Color, flush_styles};
 use log::debug;
 use pbars::{BarType, hide_cursor, PBar, show_cursor};
-use rand::{Rng, thread_rng};
-use rand_distr::{Normal, Distribution};
+use rand::{Rng, SeedableRng};
+use rand::distributions::WeightedIndex;
+use rand::rngs::StdRng;
+use rand_distr::Distribution;
+use rayon::prelude::*;
+use crate::TRAIN_STICKY;
+use crate::args::Strategy;
+use crate::crossover::{BlendCrossover, Crossover, parse_crossover};
+use crate::diversity::{niche_sizes, parse_sharing, rms_distance, Sharing, unique_genomes};
+use crate::fitness::{AbsoluteError, Fitness, parse_fitness};
+use crate::genome::{Genome, NormalGenome, parse_genome};
 use crate::input::{get_decimal, get_num, prompt};
+use crate::island::{Island, island_of, island_range, IslandSettings, IslandStats, Migration, parse_island_setting, parse_migration, Topology};
+use crate::mutation::{Mutation, parse_mutation, UniformMutation};
+use crate::sampling::Boundary;
+use crate::schedule::{Example, FixedTarget, parse_schedule, TargetSchedule};
+use crate::stopping::{parse_stop_conditions, stop_conditions_name, StopCondition, StopProgress};
 
-fn distance_from(target: u32, guess: u32) -> u32 {
-    target.abs_diff(guess)
+// the first line of every saved gang, bump the version whenever the format changes
+const SAVE_HEADER: &str = "MaLB gang v1";
+
+pub const DEFAULT_CHECKPOINT_PATH: &str = "malb_checkpoint.txt";
+
+// the burts are trained in parallel in chunks of this many, each chunk with its own rng.
+// the chunks don't depend on the number of threads, so a seed replays the same run on any machine
+const CHUNK_SIZE: usize = 4096;
+
+// the rng of a chunk, `base` is drawn from the gang's rng once per phase of a generation
+fn chunk_rng(base: u64, chunk: usize) -> StdRng {
+    StdRng::seed_from_u64(base.wrapping_add(chunk as u64))
+}
+
+fn invalid_data<S: Into<String>>(msg: S) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.into())
 }
 
-#[derive(Clone, Debug)]
+fn parse_value<T: FromStr>(key: &str, value: &str) -> io::Result<T> {
+    value.parse::<T>().map_err(|_| invalid_data(format!("Invalid value for {}: {}", key, value)))
+}
+
+// unknown values are saved as "?", the same way they are displayed
+fn parse_optional<T: FromStr>(key: &str, value: &str) -> io::Result<Option<T>> {
+    if value == "?" {
+        return Ok(None);
+    }
+    parse_value(key, value).map(Some)
+}
+
+pub(crate) fn optional_to_string<T: Display>(value: Option<T>) -> String {
+    match value {
+        Some(v) => v.to_string(),
+        None => String::from("?"),
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct Burt {
     id: u32,
 
+    // the mean score of the burt's evaluations, what it is ranked on
     score: Option<u32>,
+    // the best score and the variance of the scores of the burt's evaluations
+    best_score: Option<u32>,
+    score_variance: Option<f32>,
     guess: Option<u32>,
+    // the score of the parent a mutated burt came from, to tell if the mutation helped
+    parent_score: Option<u32>,
 
-    mu: f32,    // aka the mean
-    sigma: f32, // aka standard deviation
+    // what they mean is up to the gang's genome, i.e. [mu, sigma] for the normal genome
+    genes: Vec<f32>,
 }
 
 impl Burt {
-    pub fn new(id: u32, range: u32) -> Self {
+    pub fn new(id: u32, genome: &dyn Genome, range: u32, rng: &mut StdRng) -> Self {
         Self {
             id,
 
             score: None,
+            best_score: None,
+            score_variance: None,
             guess: None,
+            parent_score: None,
 
-            mu: thread_rng().gen_range(0.0..range as f32),
-            sigma: thread_rng().gen_range(0.0..range as f32),
+            genes: genome.random_genes(range as f32, rng),
         }
     }
 
     pub fn get_id(&self) -> u32 {
-        self.id.clone()
+        self.id
     }
-    pub fn get_mu(&self) -> f32 {
-        self.mu.clone()
-    }
-    pub fn get_sigma(&self) -> f32 {
-        self.sigma.clone()
+    pub fn get_guess(&self) -> Option<u32> {
+        self.guess
     }
     pub fn get_score_display(&self) -> String {
-        format!("{}", if self.score.is_some() {
-            self.score.unwrap().to_string()
-        } else {
-            String::from("?")
-        })
+        optional_to_string(self.score)
+    }
+    pub fn get_best_score_display(&self) -> String {
+        optional_to_string(self.best_score)
+    }
+    pub fn get_variance_display(&self) -> String {
+        match self.score_variance {
+            Some(variance) => format!("{:.2}", variance),
+            None => String::from("?"),
+        }
     }
     pub fn get_guess_display(&self) -> String {
-        format!("{}", if self.guess.is_some() {
-            self.guess.unwrap().to_string()
-        } else {
-            String::from("?")
-        })
+        optional_to_string(self.guess)
     }
 
-    pub fn training_think(&mut self, target: u32, range: u32) -> (u32, u32) {
-        // get the output of the think function with the number 1.0
-        let output = self.think(1.0, range as f32) as u32;
-        // get the distance to target as the score
-        let score = distance_from(target, output);
+    /// evaluates the burt `samples` times, each evaluation guesses every example as many times as the fitness asks for
+    /// and scores the average. the score is the mean of the evaluations and the guess is the average of every guess
+    pub fn training_think(&mut self, evaluation: &Evaluation, rng: &mut StdRng) -> (u32, u32) {
+        let Evaluation { examples, fitness, samples, genome, boundary, range } = *evaluation;
+        let guesses = examples.len() as u64 * fitness.samples() as u64;
+        let mut total_output: u64 = 0;
+        let mut score_sum: u64 = 0;
+        let mut score_squares: f64 = 0.0;
+        let mut best_score = u32::MAX;
+        for _ in 0..samples {
+            let mut eval_score: u64 = 0;
+            for example in examples {
+                for _ in 0..fitness.samples() {
+                    // get the output of the think function with the example's input
+                    let output = self.think(genome, boundary, example.input, range as f32, rng) as u32;
+                    // score the guess against the target
+                    eval_score += fitness.score(output, example.target, range) as u64;
+                    total_output += output as u64;
+                }
+            }
+            let eval_score = (eval_score / guesses) as u32;
+            score_sum += eval_score as u64;
+            score_squares += eval_score as f64 * eval_score as f64;
+            best_score = best_score.min(eval_score);
+        }
+        let output = (total_output / (guesses * samples as u64)) as u32;
+        let score = (score_sum / samples as u64) as u32;
+        let mean = score_sum as f64 / samples as f64;
         // store the score and guess
-        self.score = Some(score.clone());
+        self.score = Some(score);
+        self.best_score = Some(best_score);
+        self.score_variance = Some((score_squares / samples as f64 - mean * mean).max(0.0) as f32);
         self.guess = Some(output);
         // return those values for the training system to use
         (output, score)
     }
 
-    pub fn think(&mut self, input: f32, range: f32) -> f32 {
-        // normal distribution
-        let normal = Normal::new(self.mu, self.sigma)
-            .expect(format!("Failed to create normal for Burt #{}", self.id).as_str());
-        // get the number in the range (super inefficient, todo(eric): rework getting a number in a range)
-        let mut number = range + 1.0;
-        while number > range {
-            number = normal.sample(&mut thread_rng());
-        }
+    pub fn think(&self, genome: &dyn Genome, boundary: Boundary, input: f32, range: f32, rng: &mut StdRng) -> f32 {
+        genome.think(&self.genes, input, range, boundary, rng)
+    }
 
-        // return the number * input
-        input * number
+    /// the genes in the order crossover works on them, see `Genome::gene_names`
+    pub fn genes(&self) -> &[f32] {
+        &self.genes
     }
 
-    pub fn reeducate(&mut self, mu: f32, sigma: f32, average: bool) {
-        if average {
-            self.mu = (self.mu + mu) / 2.0;
-            self.sigma = (self.sigma + mu) / 2.0;
-        } else {
-            self.mu = mu;
-            self.sigma = sigma;
-        }
+    pub fn set_genes(&mut self, genes: &[f32]) {
+        self.genes = genes.to_vec();
     }
 
-    pub fn mutate(&mut self, mutation_rate: f32, range: u32) {
-        // todo(eric): This means that theoretically a generation can go by with no mutation
-        let mu_mut_amt = thread_rng().gen_range(0.0..((range as f32) * mutation_rate));
-        // mu
-        if thread_rng().gen_bool(0.5) {
-            self.mu += mu_mut_amt;
-            if self.mu > range as f32 {
-                self.mu = range as f32;
-            }
-        } else {
-            self.mu -= mu_mut_amt;
-            if self.mu < 0.0 {
-                self.mu = 0.0;
-            }
-        }
+    // a single line in a saved gang, i.e. "burt:id:{id},mu:{mu},sigma:{sigma},score:{score},best_score:{best_score},
+    // score_variance:{score_variance},guess:{guess},parent_score:{parent_score}" with a field for every gene
+    fn to_record(&self, genome: &dyn Genome) -> String {
+        let genes: Vec<String> = genome.gene_names().iter()
+            .zip(&self.genes)
+            .map(|(name, gene)| format!("{}:{}", name, gene))
+            .collect();
+        format!("burt:id:{},{},score:{},best_score:{},score_variance:{},guess:{},parent_score:{}",
+                self.id, genes.join(","), optional_to_string(self.score), optional_to_string(self.best_score),
+                optional_to_string(self.score_variance), optional_to_string(self.guess),
+                optional_to_string(self.parent_score))
+    }
 
-        let sigma_mut_amt = thread_rng().gen_range(0.0..((range as f32) * mutation_rate));
-        // sigma
-        if thread_rng().gen_bool(0.5) {
-            self.sigma += sigma_mut_amt;
-            if self.sigma > range as f32 {
-                self.sigma = range as f32;
-            }
-        } else {
-            self.sigma -= sigma_mut_amt;
-            if self.sigma < 0.0 {
-                self.sigma = 0.0;
-            }
+    fn from_record(record: &str, genome: &dyn Genome) -> io::Result<Self> {
+        let mut fields = Vec::new();
+        for field in record.split(',') {
+            let (key, value) = field.split_once(':')
+                .ok_or_else(|| invalid_data(format!("Invalid burt field: {}", field)))?;
+            fields.push((key, value));
         }
+        let get = |key: &str| fields.iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| *v)
+            .ok_or_else(|| invalid_data(format!("Burt is missing {}: {}", key, record)));
+
+        Ok(Self {
+            id: parse_value("id", get("id")?)?,
+
+            score: parse_optional("score", get("score")?)?,
+            best_score: parse_optional("best_score", get("best_score").unwrap_or("?"))?,
+            score_variance: parse_optional("score_variance", get("score_variance").unwrap_or("?"))?,
+            guess: parse_optional("guess", get("guess")?)?,
+            parent_score: parse_optional("parent_score", get("parent_score").unwrap_or("?"))?,
+
+            genes: genome.gene_names().iter()
+                .map(|name| parse_value(name, get(name)?))
+                .collect::<io::Result<Vec<f32>>>()?,
+        })
+    }
+}
+
+/// what the burts are evaluated on, the same for every burt of a generation
+#[derive(Copy, Clone)]
+pub struct Evaluation<'a> {
+    pub examples: &'a [Example],
+    pub fitness: &'a dyn Fitness,
+    // how many times every burt is evaluated
+    pub samples: u32,
+    pub genome: &'a dyn Genome,
+    pub boundary: Boundary,
+    pub range: u32,
+}
+
+/// picks the parents of the burts that get re-educated each generation
+pub trait SelectionStrategy: Send + Sync {
+    /// i.e. "tournament:3", what `parse_selection` takes to make the strategy again
+    fn name(&self) -> String;
+
+    /// `scores` are sorted from best (lowest) to worst and the first `survivors` of them survive the generation,
+    /// returns `count` indices into `scores` of the burts to copy
+    fn select(&self, scores: &[u32], survivors: usize, count: usize, rng: &mut StdRng) -> Vec<usize>;
+}
+
+/// a random survivor, the original behaviour
+pub struct Truncation;
+
+impl SelectionStrategy for Truncation {
+    fn name(&self) -> String {
+        String::from("truncation")
+    }
+
+    fn select(&self, _scores: &[u32], survivors: usize, count: usize, rng: &mut StdRng) -> Vec<usize> {
+        (0..count).map(|_| rng.gen_range(0..survivors)).collect()
+    }
+}
+
+/// the best of `size` random burts
+pub struct Tournament {
+    pub size: usize,
+}
+
+impl SelectionStrategy for Tournament {
+    fn name(&self) -> String {
+        format!("tournament:{}", self.size)
+    }
+
+    fn select(&self, scores: &[u32], _survivors: usize, count: usize, rng: &mut StdRng) -> Vec<usize> {
+        // the scores are sorted, so the lowest index is the best burt
+        (0..count).map(|_| {
+            (0..self.size).map(|_| rng.gen_range(0..scores.len())).min().unwrap()
+        }).collect()
+    }
+}
+
+/// fitness proportional, a burt's chance is 1 / (1 + score)
+pub struct Roulette;
+
+impl SelectionStrategy for Roulette {
+    fn name(&self) -> String {
+        String::from("roulette")
+    }
+
+    fn select(&self, scores: &[u32], _survivors: usize, count: usize, rng: &mut StdRng) -> Vec<usize> {
+        let wheel = WeightedIndex::new(scores.iter().map(|s| 1.0 / (1.0 + *s as f64)))
+            .expect("Failed to make the roulette wheel");
+        (0..count).map(|_| wheel.sample(rng)).collect()
+    }
+}
+
+/// linear ranking, the best burt gets a weight of n and the worst a weight of 1
+pub struct LinearRank;
+
+impl SelectionStrategy for LinearRank {
+    fn name(&self) -> String {
+        String::from("rank")
+    }
+
+    fn select(&self, scores: &[u32], _survivors: usize, count: usize, rng: &mut StdRng) -> Vec<usize> {
+        let n = scores.len();
+        let ranks = WeightedIndex::new((0..n).map(|x| (n - x) as f64))
+            .expect("Failed to make the rank weights");
+        (0..count).map(|_| ranks.sample(rng)).collect()
+    }
+}
+
+/// splits "name:arg" (or "name arg") into the name and everything after it, the way every setting is written
+pub fn split_spec(spec: &str) -> (&str, Option<&str>) {
+    match spec.split_once([':', ' ']) {
+        Some((name, arg)) => (name, Some(arg.trim())),
+        None => (spec, None),
+    }
+}
+
+/// parses a selection strategy from "truncation", "tournament[:size]", "roulette" or "rank"
+pub fn parse_selection(spec: &str) -> Result<Box<dyn SelectionStrategy>, String> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let (name, arg) = split_spec(&spec);
+
+    match (name, arg) {
+        ("truncation", None) => Ok(Box::new(Truncation)),
+        ("tournament", None) => Ok(Box::new(Tournament { size: 3 })),
+        ("tournament", Some(size)) => match size.parse::<usize>() {
+            Ok(size) if size > 0 => Ok(Box::new(Tournament { size })),
+            _ => Err(String::from("The tournament size must be at least 1!")),
+        },
+        ("roulette", None) => Ok(Box::new(Roulette)),
+        ("rank", None) => Ok(Box::new(LinearRank)),
+        _ => Err(format!("Unknown selection strategy: {} (expected truncation, tournament[:size], roulette or rank)", spec)),
     }
 }
 
+/// the stats of a single generation, taken after the burts guessed and before they were re-educated
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenerationStats {
+    pub generation: u32,
+    pub min_score: u32,
+    pub mean_score: f32,
+    pub median_score: u32,
+    pub max_score: u32,
+    pub mean_guess: f32,
+    // the standard deviation of every gene, in the order of the genome's genes
+    pub gene_stddevs: Vec<f32>,
+    pub perfect_count: u32,
+    // how many different sets of genes there are, and the root mean square distance between two burts' genes
+    pub unique_genomes: u32,
+    pub rms_distance: f32,
+}
+
+// the standard deviation of a gene from the sum of the gene and the sum of its squares
+fn stddev(sum: f64, sum_squares: f64, count: usize) -> f32 {
+    let mean = sum / count as f64;
+    (sum_squares / count as f64 - mean * mean).max(0.0).sqrt() as f32
+}
+
 pub struct BurtGang {
     pub burts: Vec<Burt>,
     pub range: u32,
@@ -134,40 +349,157 @@ pub struct BurtGang {
     pub current_generation: u32,
     pub survival_rate: f32,
     pub mutation_rate: f32,
+    pub seed: u64,
+    // save to checkpoint_path every checkpoint_every generations, 0 turns it off
+    pub checkpoint_every: u32,
+    pub checkpoint_path: PathBuf,
+    pub selection: Box<dyn SelectionStrategy>,
+    pub crossover: Box<dyn Crossover>,
+    // how often a new burt is made from two parents instead of copying one, 0 turns crossover off
+    pub crossover_rate: f32,
+    pub mutation: Box<dyn Mutation>,
+    // how many of the best burts of every island are copied into the next generation untouched,
+    // so k islands keep k times as many
+    pub elite_count: u32,
+    // moves the target between generations, the fixed schedule never does
+    pub schedule: Box<dyn TargetSchedule>,
+    pub fitness: Box<dyn Fitness>,
+    // how many times every burt is evaluated each generation, it is ranked on the mean score
+    pub samples_per_eval: u32,
+    // what the burts' genes mean, change it with `set_genome` so the burts get new genes
+    pub genome: Box<dyn Genome>,
+    // what happens to guesses that fall outside of the range
+    pub boundary: Boundary,
+    // the islands the burts are split into, there is always at least one. change how many with `set_islands`
+    pub islands: Vec<Island>,
+    pub migration: Migration,
+    // ranks the burts on scores made worse by crowding, to keep them from all settling on the same genes
+    pub sharing: Sharing,
+    // training stops early once any of these is met, see `stop_reason`
+    pub stop_when: Vec<StopCondition>,
+    // trained with the sticky strategy instead of the normal one, see `train`
+    pub sticky: bool,
+
+    // every random decision of the gang comes from this, so a seed always replays the same run
+    rng: StdRng,
 
     average_guess: Option<u32>,
     average_score: Option<u32>,
+    best_score: Option<u32>,
+    perfect_count: u32,
+    // the stats of every generation trained so far, oldest first
+    history: Vec<GenerationStats>,
+    // how each island did in the last generation
+    island_stats: Vec<IslandStats>,
+    stop_progress: StopProgress,
 }
 
 impl BurtGang {
-    pub fn new(burts: Vec<Burt>, range: u32, target: u32, generations: u32, survival_rate: f32, mutation_rate: f32) -> Self {
+    pub fn new(range: u32, target: u32, generations: u32, survival_rate: f32, mutation_rate: f32, seed: u64) -> Self {
         Self {
-            burts,
+            burts: Vec::new(),
             range,
             target,
             current_generation: 0,
             generations,
             survival_rate,
             mutation_rate,
+            seed,
+            checkpoint_every: 0,
+            checkpoint_path: PathBuf::from(DEFAULT_CHECKPOINT_PATH),
+            selection: Box::new(Truncation),
+            crossover: Box::new(BlendCrossover),
+            crossover_rate: 0.0,
+            mutation: Box::new(UniformMutation),
+            elite_count: 0,
+            schedule: Box::new(FixedTarget),
+            fitness: Box::new(AbsoluteError),
+            samples_per_eval: 1,
+            genome: Box::new(NormalGenome),
+            boundary: Boundary::default(),
+            islands: vec![Island::default()],
+            migration: Migration::default(),
+            sharing: Sharing::default(),
+            stop_when: Vec::new(),
+            sticky: TRAIN_STICKY,
+
+            rng: StdRng::seed_from_u64(seed),
 
             average_guess: None,
             average_score: None,
+            best_score: None,
+            perfect_count: 0,
+            history: Vec::new(),
+            island_stats: Vec::new(),
+            stop_progress: StopProgress::default(),
         }
     }
 
-    pub fn iter(&self) -> core::slice::Iter<Burt>{
-        self.burts.iter()
+    /// a new gang with the same settings and seed, but without any burts or training, i.e. to start the run over.
+    /// the operators are made again from their names, the same way a saved gang is loaded
+    pub fn settings_copy(&self) -> Self {
+        let mut copy = Self::new(self.range, self.target, self.generations, self.survival_rate, self.mutation_rate, self.seed);
+        let reparse = "The name of an operator always parses back into the operator";
+        copy.checkpoint_every = self.checkpoint_every;
+        copy.checkpoint_path = self.checkpoint_path.clone();
+        copy.selection = parse_selection(&self.selection.name()).expect(reparse);
+        copy.crossover = parse_crossover(&self.crossover.name()).expect(reparse);
+        copy.crossover_rate = self.crossover_rate;
+        copy.mutation = parse_mutation(&self.mutation.name()).expect(reparse);
+        copy.elite_count = self.elite_count;
+        copy.schedule = parse_schedule(&self.schedule.name()).expect(reparse);
+        copy.fitness = parse_fitness(&self.fitness.name()).expect(reparse);
+        copy.samples_per_eval = self.samples_per_eval;
+        copy.genome = parse_genome(&self.genome.name()).expect(reparse);
+        copy.boundary = self.boundary;
+        copy.set_islands(self.islands.len());
+        for (x, island) in self.islands.iter().enumerate() {
+            for (var, value) in island.settings() {
+                copy.set_island(x, var, &value).expect(reparse);
+            }
+        }
+        copy.migration = self.migration;
+        copy.sharing = self.sharing;
+        copy.stop_when = self.stop_when.clone();
+        copy.sticky = self.sticky;
+        copy
     }
 
-    pub fn get(&self, x: usize) -> &Burt {
-        self.burts.get(x).expect("Failed to get burt from gang!")
+    /// replaces the current burts with `burt_count` new ones drawn from the gang's rng
+    pub fn populate(&mut self, burt_count: u32, display: bool) {
+        self.burts = populate_burts(burt_count, self.genome.as_ref(), self.range, display, &mut self.rng);
     }
 
-    pub fn len(&self) -> usize {
-        self.burts.len()
+    /// switches to another genome, the old genes mean nothing to it so every burt is replaced with a new one
+    pub fn set_genome(&mut self, genome: Box<dyn Genome>) {
+        self.genome = genome;
+        self.populate(self.burts.len() as u32, false);
+    }
+
+    /// splits the burts into `count` islands, the settings of the islands that are kept stay the same
+    pub fn set_islands(&mut self, count: usize) {
+        self.islands.resize_with(count.max(1), Island::default);
+        self.island_stats.clear();
+    }
+
+    /// sets one of an island's own settings, see `Island::set`
+    pub fn set_island(&mut self, island: usize, var: &str, value: &str) -> Result<(), String> {
+        let count = self.islands.len();
+        match self.islands.get_mut(island) {
+            Some(island) => island.set(var, value),
+            None => Err(format!("There is no island {} (the islands are 0 to {})", island, count - 1)),
+        }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Burt>{
+        self.burts.iter()
     }
 
     pub fn train(&mut self, advanced: bool) {
+        // an empty gang has nothing to rank
+        if self.burts.is_empty() {
+            return;
+        }
         if advanced {
             self.train_sticky();
         } else {
@@ -178,141 +510,320 @@ impl BurtGang {
     fn train_sticky(&mut self) {
         self.current_generation += 1;
 
-        // store variables for averages
-        let mut total_score: usize = 0;
-        let mut total_guess: usize = 0;
-        let mut burt_size: u32 = 0;
+        // have the burts guess and rank them from best to worst
+        let (ranking, scores) = self.evaluate();
 
-        // loop through the burts and have them guess
-        for b in &mut self.burts {
-            // have the current burt guess
-            let (guess, score) = b.training_think(self.target, self.range);
-            total_guess += guess as usize;
-            total_score += score as usize;
-            burt_size += 1;
+        // print out the best burt's info
+        let best_burt = &self.burts[ranking[0]];
+        debug!(target:"MaLB.train.stick", "Best burt of generation {}/{}: {} with a guess of {} and a score of {}, genes: {}",
+            self.current_generation, self.generations, best_burt.id, best_burt.get_guess_display(), best_burt.get_score_display(),
+            self.genome.describe(&best_burt.genes, self.range as f32));
+        debug!(target:"MaLB.train.stick", "Perfect burts this generation: {}", self.perfect_count);
+
+        let islands = self.rank_islands(ranking, scores);
+        let moves = self.pick_migrants(&islands);
+        let mut mutated = 0;
+        for (island, (ranking, scores)) in islands.iter().enumerate() {
+            // more islands than burts leaves some of them empty
+            if ranking.is_empty() {
+                continue;
+            }
+            // the perfect burts and the elites are spared, every other burt gets a replacement.
+            // sharing can rank a perfect burt below imperfect ones, so they're picked by their real scores
+            let elites = (self.elite_count as usize).min(ranking.len());
+            let replaced: Vec<usize> = ranking.iter()
+                .enumerate()
+                .filter(|(rank, x)| *rank >= elites && self.burts[**x].score != Some(0))
+                .map(|(_, x)| *x)
+                .collect();
+            let perfect_count = ranking.iter().filter(|x| self.burts[**x].score == Some(0)).count();
+            let survival_amt = ((ranking.len() as f32 * self.survival_rate_of(island)) as usize).max(1);
+            // if all the burts but one are perfect, don't mutate
+            let mutate = perfect_count != ranking.len() - 1;
+            self.reproduce(island, ranking, scores, survival_amt, &replaced, mutate);
+            if mutate {
+                mutated += replaced.len();
+            }
         }
+        self.migrate(moves);
 
-        // set the averages
-        self.average_guess = Some((total_guess / burt_size as usize) as u32);
-        self.average_score = Some((total_score / burt_size as usize) as u32);
-
-        // sort the burts into [0] best -> [len() - 1] worst
-        let mut sorted_burts: Vec<Burt> = Vec::new();
-
-        // store the best burt
-        let mut best_set: Option<(u32, f32, f32)> = None;
-
-        // loop through the burts to sort them and find the best scoring burt
-        // worst case this will loop through all Burts twice :/ (can be in the thousands if not millions)
-        while !self.burts.is_empty() {
-            // get the current burt
-            let b = self.burts.remove(0);
-
-            // if the current burt is better than the current best, overwrite the current best
-            if let Some((score, _, _)) = best_set {
-                // if it exists and the current one is better
-                if score > b.score.unwrap() {
-                    best_set = Some((b.score.unwrap(), b.mu, b.sigma));
-                }
-            } else {
-                // there is no current best
-                best_set = Some((b.score.unwrap(), b.mu, b.sigma));
-            }
+        debug!(target:"MaLB.train.stick", "Mutated Burts: {}", mutated);
+    }
 
-            let mut placed = false;
-            // loop through the sorted burts
-            for x in 0..sorted_burts.len() {
-                // get the current sorted burt (sb)
-                let sb = sorted_burts.get(x).unwrap();
-                // if the current burt has a better or equal score
-                if (&b).score.unwrap() <= sb.score.unwrap() {
-                    // place the burt in the sorted burts list at the location
-                    sorted_burts.insert(x, b.clone());
-                    placed = true;
-                    // break the loop
-                    break;
+    // splits the gang's ranking into the ranking of every island and records how each island did.
+    // the gang's ranking is already in order, so each island's part of it is too
+    fn rank_islands(&mut self, ranking: Vec<usize>, scores: Vec<u32>) -> Vec<(Vec<usize>, Vec<u32>)> {
+        let count = self.islands.len();
+        let burt_count = self.burts.len();
+        let islands = if count == 1 {
+            vec![(ranking, scores)]
+        } else {
+            let mut islands = vec![(Vec::new(), Vec::new()); count];
+            for (x, score) in ranking.into_iter().zip(scores) {
+                let island = &mut islands[island_of(x, count, burt_count)];
+                island.0.push(x);
+                island.1.push(score);
+            }
+            islands
+        };
+
+        // from the real scores, like the gang's stats
+        self.island_stats = islands.iter().map(|(ranking, _)| {
+            let burts = ranking.len();
+            let (mut guesses, mut total_score, mut sum, mut squares) = (0u64, 0u64, 0.0f64, 0.0f64);
+            let (mut best_score, mut perfect_count) = (None, 0);
+            for &x in ranking {
+                let b = &self.burts[x];
+                let score = b.score.unwrap();
+                guesses += b.guess.unwrap_or(0) as u64;
+                total_score += score as u64;
+                best_score = Some(best_score.map_or(score, |best: u32| best.min(score)));
+                if score == 0 {
+                    perfect_count += 1;
                 }
+                let gene = b.genes[0] as f64;
+                sum += gene;
+                squares += gene * gene;
             }
-            // if it hasn't been placed
-            // push the burt at the end (its a bad burt )
-            if !placed {
-                sorted_burts.push(b);
+            IslandStats {
+                burts,
+                average_guess: (burts > 0).then(|| (guesses / burts as u64) as u32),
+                average_score: (burts > 0).then(|| (total_score / burts as u64) as u32),
+                best_score,
+                perfect_count,
+                gene_stddev: if burts > 0 { stddev(sum, squares, burts) } else { 0.0 },
             }
-        }
-
-        // loop through the sorted burts and if they are not perfect this round, mutate
-        let (bscore, bmu, bsigma) = best_set.unwrap();
-        // for b in &mut sorted_burts {
-        //     b.reeducate(bmu, bsigma);
-        //     if bscore != 0 {
-        //         // if the best score is not 0, mutate this score
-        //         debug!("Best score this round is {}. Mutating next generation", bscore);
-        //         b.mutate(self.mutation_rate, self.range);
-        //     }
-        // }
+        }).collect();
+        islands
+    }
 
-        debug!(target:"MaLB.train.stick", "STORED BEST VALUES: score: {}, mu: {}, sigma: {}", bscore, bmu, bsigma);
+    // the survival rate, mutation rate and selection of an island, its own or the gang's
+    fn survival_rate_of(&self, island: usize) -> f32 {
+        self.islands[island].survival_rate.unwrap_or(self.survival_rate)
+    }
+    fn mutation_rate_of(&self, island: usize) -> f32 {
+        self.islands[island].mutation_rate.unwrap_or(self.mutation_rate)
+    }
 
-        // print out the best burt's info
-        let best_burt = sorted_burts.get(0).unwrap();
-        debug!(target:"MaLB.train.stick", "Best burt of generation {}/{}: {} with a guess of {} and a score of {}",
-            self.current_generation, self.generations, best_burt.id, best_burt.get_guess_display(), best_burt.get_score_display());
+    // every `migration.every` generations picks the best burts of every island to copy over the worst burts
+    // of the next island (or of a random other one). the migrants' genes are taken here, before the islands breed,
+    // since breeding can replace the migrants themselves. returns the burts to overwrite along with the genes
+    fn pick_migrants(&mut self, islands: &[(Vec<usize>, Vec<u32>)]) -> Vec<(usize, Vec<f32>)> {
+        let count = islands.len();
+        let Migration { topology, every, migrants } = self.migration;
+        let mut moves = Vec::new();
+        if count < 2 || every == 0 || migrants == 0 || !self.current_generation.is_multiple_of(every) {
+            return moves;
+        }
 
-        // get the amount of burts that guessed correctly for debugging
-        let mut amt_perfect = 0;
-        for b in &sorted_burts {
-            if b.score.unwrap() != 0 {
-                break;
-            }
-            amt_perfect += 1;
-        }
-        debug!(target:"MaLB.train.stick", "Perfect burts this generation: {}", amt_perfect);
-
-        let mut mutated_burts: u32 = 0;
-        let burts2 = sorted_burts.clone();
-
-        // go through and re-sort the burts based on id
-        let mut new_burts: Vec<Burt> = Vec::new();
-        while !sorted_burts.is_empty() {
-            let mut current = sorted_burts.remove(0);
-
-            // mutate the burts that need it
-            // if the current score is not 0 (not perfect)
-            if current.score.unwrap() != 0 {
-                let survival_amt = (burts2.len() as f32 * self.survival_rate) as usize;
-                let selected_best = burts2.get(thread_rng().gen_range(0..survival_amt)).unwrap();
-                // change the current's values to bmu and bsigma
-                current.reeducate(selected_best.mu, selected_best.sigma, false);
-                //current.reeducate(bmu, bsigma, false);
-
-                // if not all the burts are not perfect
-                if amt_perfect != burt_size - 1 {
-                    mutated_burts += 1;
-                    // mutate current's values
-                    current.mutate(self.mutation_rate, self.range);
+        // how many of the worst burts of each island have been replaced so far
+        let mut replaced = vec![0; count];
+        for from in 0..count {
+            let to = match topology {
+                Topology::Ring => (from + 1) % count,
+                Topology::Random => {
+                    let to = self.rng.gen_range(0..count - 1);
+                    if to >= from { to + 1 } else { to }
                 }
+            };
+            let (source, destination) = (&islands[from].0, &islands[to].0);
+            let moving = (migrants as usize).min(source.len()).min(destination.len() - replaced[to]);
+            for &x in &source[..moving] {
+                replaced[to] += 1;
+                moves.push((destination[destination.len() - replaced[to]], self.burts[x].genes.clone()));
             }
+            debug!(target:"MaLB.train", "{} burts migrated from island {} to island {}", moving, from, to);
+        }
+        moves
+    }
+
+    // copies the migrants picked by `pick_migrants` over the burts they replace, once the islands have bred
+    fn migrate(&mut self, moves: Vec<(usize, Vec<f32>)>) {
+        for (x, genes) in moves {
+            let burt = &mut self.burts[x];
+            burt.set_genes(&genes);
+            // a migrant isn't a mutation
+            burt.parent_score = None;
+        }
+    }
+
+    // has every burt guess and sets the gang's stats, then ranks the burts from best to worst.
+    // returns the indices of the burts in rank order along with their scores in the same order,
+    // the burts themselves are never moved so they stay in id order
+    fn evaluate(&mut self) -> (Vec<usize>, Vec<u32>) {
+        // this generation's examples, with the targets kept in the range in case it shrank
+        let range = self.range;
+        let examples: Vec<Example> = self.schedule.examples(self.current_generation, self.target, range, &mut self.rng)
+            .into_iter()
+            .map(|example| Example { target: example.target.min(range - 1), ..example })
+            .collect();
+        self.target = examples[0].target;
+
+        // have the burts guess in parallel, adding up the guesses and scores for the averages
+        let base: u64 = self.rng.gen();
+        let evaluation = Evaluation {
+            examples: &examples,
+            fitness: self.fitness.as_ref(),
+            samples: self.samples_per_eval.max(1),
+            genome: self.genome.as_ref(),
+            boundary: self.boundary,
+            range,
+        };
+        let (total_guess, total_score) = self.burts.par_chunks_mut(CHUNK_SIZE)
+            .enumerate()
+            .map(|(chunk, burts)| {
+                let mut rng = chunk_rng(base, chunk);
+                burts.iter_mut().fold((0usize, 0usize), |(total_guess, total_score), b| {
+                    let (guess, score) = b.training_think(&evaluation, &mut rng);
+                    (total_guess + guess as usize, total_score + score as usize)
+                })
+            })
+            .reduce(|| (0, 0), |a, b| (a.0 + b.0, a.1 + b.1));
+        self.adapt_mutation();
 
-            // place it in the correct location based on it's id
-            let mut placed = false;
-            for x in 0..new_burts.len() {
-                let b = new_burts.get(x).unwrap();
-                if current.id < b.id {
-                    new_burts.insert(x, current.clone());
-                    placed = true;
-                    break;
+        // set the averages
+        self.average_guess = Some((total_guess / self.burts.len()) as u32);
+        self.average_score = Some((total_score / self.burts.len()) as u32);
+
+        // with sharing on, the burts are ranked on their shared scores instead
+        let niches = self.sharing.is_on().then(|| niche_sizes(&self.burts, self.islands.len(), self.sharing.radius));
+        let sharing = self.sharing;
+
+        // sorting (score, index) pairs keeps burts with the same score in id order
+        let mut ranked: Vec<(u32, usize)> = self.burts.iter()
+            .enumerate()
+            .map(|(x, b)| match &niches {
+                Some(niches) => (sharing.shared_score(b.score.unwrap(), niches[x]), x),
+                None => (b.score.unwrap(), x),
+            })
+            .collect();
+        ranked.par_sort_unstable();
+        let (scores, ranking): (Vec<u32>, Vec<usize>) = ranked.into_iter().unzip();
+
+        // the stats are of the real scores, which sharing puts out of order
+        let real_scores = niches.is_some().then(|| {
+            let mut real_scores: Vec<u32> = self.burts.iter().map(|b| b.score.unwrap()).collect();
+            real_scores.par_sort_unstable();
+            real_scores
+        });
+        let sorted = real_scores.as_deref().unwrap_or(&scores);
+
+        self.best_score = sorted.first().copied();
+        self.perfect_count = sorted.iter().take_while(|s| **s == 0).count() as u32;
+
+        // the spread of the genes that made these guesses. the chunks are added up in order,
+        // so the sums are the same on any number of threads
+        let gene_count = self.genome.gene_names().len();
+        let chunks: Vec<(Vec<f64>, Vec<f64>)> = self.burts.par_chunks(CHUNK_SIZE)
+            .map(|burts| {
+                let mut sums = vec![0.0; gene_count];
+                let mut squares = vec![0.0; gene_count];
+                for b in burts {
+                    for (x, gene) in b.genes.iter().enumerate() {
+                        sums[x] += *gene as f64;
+                        squares[x] += (*gene as f64) * (*gene as f64);
+                    }
                 }
-            }
-            if !placed {
-                new_burts.push(current);
-            }
+                (sums, squares)
+            })
+            .collect();
+        let (mut gene_sums, mut gene_squares) = (vec![0.0; gene_count], vec![0.0; gene_count]);
+        for (sums, squares) in chunks {
+            gene_sums.iter_mut().zip(sums).for_each(|(total, sum)| *total += sum);
+            gene_squares.iter_mut().zip(squares).for_each(|(total, square)| *total += square);
         }
+        let count = sorted.len();
+        let gene_stddevs: Vec<f32> = gene_sums.iter().zip(&gene_squares).map(|(sum, squares)| stddev(*sum, *squares, count)).collect();
+        self.history.push(GenerationStats {
+            generation: self.current_generation,
+            min_score: sorted[0],
+            mean_score: (total_score as f64 / count as f64) as f32,
+            median_score: sorted[count / 2],
+            max_score: sorted[count - 1],
+            mean_guess: (total_guess as f64 / count as f64) as f32,
+            rms_distance: rms_distance(&gene_stddevs, count),
+            gene_stddevs,
+            perfect_count: self.perfect_count,
+            unique_genomes: unique_genomes(&self.burts),
+        });
+        self.stop_progress.update(self.history.last().unwrap());
+
+        (ranking, scores)
+    }
 
-        drop(burts2);
+    // replaces the genes of every burt ranked `spared` or worse with a child bred from the ranking of an island,
+    // mutating the children if `mutate` is set
+    fn reproduce(&mut self, island: usize, ranking: &[usize], scores: &[u32], survivors: usize, replaced: &[usize], mutate: bool) {
+        let children = self.breed(island, ranking, scores, survivors, replaced.len(), mutate);
+        for (&x, (genes, parent_score)) in replaced.iter().zip(children) {
+            let current = &mut self.burts[x];
+            current.set_genes(&genes);
+            if mutate {
+                current.parent_score = parent_score;
+            }
+        }
+    }
 
-        debug!(target:"MaLB.train.stick", "Mutated Burts: {}", mutated_burts);
+    // picks the parents of `count` new burts from the ranking and works out the new burts' genes in parallel,
+    // crossing two parents over `crossover_rate` of the time and copying the first one otherwise
+    // (along with the score of the first parent)
+    fn breed(&mut self, island: usize, ranking: &[usize], scores: &[u32], survivors: usize, count: usize, mutate: bool) -> Vec<(Vec<f32>, Option<u32>)> {
+        let mutation_rate = self.mutation_rate_of(island);
+        let selection = self.islands[island].selection.as_ref().unwrap_or(&self.selection);
+        let mothers = selection.select(scores, survivors, count, &mut self.rng);
+        // only pick the second parents when they can be used, so runs without crossover don't change
+        let fathers = if self.crossover_rate > 0.0 {
+            selection.select(scores, survivors, count, &mut self.rng)
+        } else {
+            mothers.clone()
+        };
+
+        let base: u64 = self.rng.gen();
+        let range = self.range as f32;
+        let burts = &self.burts;
+        let genome = self.genome.as_ref();
+        let crossover = self.crossover.as_ref();
+        let crossover_rate = self.crossover_rate;
+        let mutation = self.mutation.as_ref();
+        mothers.par_chunks(CHUNK_SIZE)
+            .zip(fathers.par_chunks(CHUNK_SIZE))
+            .enumerate()
+            .flat_map_iter(|(chunk, (mothers, fathers))| {
+                let mut rng = chunk_rng(base, chunk);
+                mothers.iter().zip(fathers).map(|(&m, &f)| {
+                    let mother = burts[ranking[m]].genes();
+                    let mut genes = if crossover_rate > 0.0 && rng.gen_bool(crossover_rate as f64) {
+                        genome.cross(mother, burts[ranking[f]].genes(), crossover, range, &mut rng)
+                    } else {
+                        mother.to_vec()
+                    };
+                    if mutate {
+                        genome.mutate(&mut genes, mutation, mutation_rate, range, &mut rng);
+                    }
+                    // the parent's real score, a mutation is judged on the score it got
+                    (genes, burts[ranking[m]].score)
+                }).collect::<Vec<_>>()
+            })
+            .collect()
+    }
 
-        self.burts = new_burts;
+    // tells the mutation how many of last generation's mutated burts beat their parent
+    fn adapt_mutation(&mut self) {
+        let mut mutated = 0;
+        let mut improved = 0;
+        for b in &mut self.burts {
+            if let Some(parent_score) = b.parent_score.take() {
+                mutated += 1;
+                if b.score.unwrap() < parent_score {
+                    improved += 1;
+                }
+            }
+        }
+        if mutated > 0 {
+            self.mutation.adapt(improved, mutated);
+            debug!(target:"MaLB.train", "{}/{} mutations improved on their parent, mutation is now {}",
+                improved, mutated, self.mutation.name());
+        }
     }
 
     fn train_normal(&mut self) {
@@ -325,144 +836,308 @@ impl BurtGang {
         // increase generation
         self.current_generation += 1;
 
-        // store variables for averages
-        let mut total_score: usize = 0;
-        let mut total_guess: usize = 0;
-        let mut runs: u32 = 0;
+        // have the burts guess and rank them from best to worst
+        let (ranking, scores) = self.evaluate();
 
-        // loop through the burts and have them guess
-        for b in &mut self.burts {
-            let (guess, score) = b.training_think(self.target, self.range);
-            total_guess += guess as usize;
-            total_score += score as usize;
-            runs += 1;
-        }
+        let best_burt = &self.burts[ranking[0]];
+        debug!(target:"MaLB.train.norm", "Best burt of generation {}/{}: {} with a guess of {} and a score of {}",
+            self.current_generation, self.generations, best_burt.id, best_burt.get_guess_display(), best_burt.get_score_display());
+        debug!(target:"MaLB.train.norm", "Perfect burts this generation: {}", self.perfect_count);
 
-        // set the averages
-        self.average_guess = Some((total_guess / runs as usize) as u32);
-        self.average_score = Some((total_score / runs as usize) as u32);
-
-        // sort the burts into [0] best -> [len() - 1] worst
-        let mut sorted_burts: Vec<Burt> = Vec::new();
-
-        // loop through the burts
-        // worst case this will loop through all Burts twice :/ (can be in the thousands if not millions)
-        while !self.burts.is_empty() {
-            // get the current burt
-            let b = self.burts.remove(0);
-            let mut placed = false;
-            // loop through the sorted burts
-            for x in 0..sorted_burts.len() {
-                // get the current sorted burt (sb)
-                let sb = sorted_burts.get(x).unwrap();
-                // if the current burt has a better or equal score
-                if (&b).score.unwrap() <= sb.score.unwrap() {
-                    // place the burt in the sorted burts list at the location
-                    sorted_burts.insert(x, b.clone());
-                    placed = true;
-                    // break the loop
-                    break;
-                }
-            }
-            // if it hasn't been placed
-            // push the burt at the end (its a bad burt )
-            if !placed {
-                sorted_burts.push(b);
+        // with the ranking of each island, "re-educate" the lesser Burts
+        let islands = self.rank_islands(ranking, scores);
+        let moves = self.pick_migrants(&islands);
+        for (island, (ranking, scores)) in islands.iter().enumerate() {
+            if ranking.is_empty() {
+                continue;
             }
+            let survival_amt = ((ranking.len() as f32 * self.survival_rate_of(island)) as usize).max(1);
+            // the elites always survive, even if the survival rate is lower
+            let survival_amt = survival_amt.max(self.elite_count as usize).min(ranking.len());
+            // breed a replacement for every burt that didn't survive
+            self.reproduce(island, ranking, scores, survival_amt, &ranking[survival_amt..], true);
         }
+        self.migrate(moves);
+    }
+
+    /// the burt with the best score of the last generation, the lowest id of the best if there's a tie
+    pub fn best_burt(&self) -> Option<&Burt> {
+        self.burts.iter().filter(|b| b.score.is_some()).min_by_key(|b| b.score)
+    }
 
-        // with the sorted burt list, "re-educate" the lesser Burts
-        let mut survival_amt = (sorted_burts.len() as f32 * self.survival_rate) as u32;
-        if survival_amt < 1 {
-            survival_amt = 1;
+    /// the average genes of the burts that survived the last generation
+    pub fn best_average(&self) -> Option<Vec<f32>> {
+        let mut scored: Vec<&Burt> = self.burts.iter().filter(|b| b.score.is_some()).collect();
+        if scored.is_empty() {
+            return None;
         }
-        // split sorted_burts into 2 vectors:
-        // original (sorted_burts) - contains the burts that survived
-        // bad_burts               - contains the burts that need to be re-educated
-        let mut bad_burts = sorted_burts.split_off(survival_amt as usize);
+        scored.sort_by_key(|b| b.score);
+
+        let survival_amt = ((scored.len() as f32 * self.survival_rate) as usize).max(1);
+        let best = &scored[..survival_amt];
+        let genes = (0..self.genome.gene_names().len())
+            .map(|x| best.iter().map(|b| b.genes[x]).sum::<f32>() / best.len() as f32)
+            .collect();
+        Some(genes)
+    }
 
-        let best_burt = sorted_burts.get(0).unwrap();
-        debug!(target:"MaLB.train.norm", "Best burt of generation {}/{}: {} with a guess of {} and a score of {}",
-            self.current_generation, self.generations, best_burt.id, best_burt.get_guess_display(), best_burt.get_score_display());
+    fn best_average_record(&self) -> Option<String> {
+        self.best_average().map(|genes| {
+            let genes: Vec<String> = self.genome.gene_names().iter()
+                .zip(genes)
+                .map(|(name, gene)| format!("{}:{}", name, gene))
+                .collect();
+            format!("target:{},{}", self.target, genes.join(","))
+        })
+    }
 
-        let mut amt_perfect = 0;
-        for b in &sorted_burts {
-            if b.score.unwrap() != 0 {
-                break;
-            }
-            amt_perfect += 1;
-        }
-        debug!(target:"MaLB.train.norm", "Perfect burts this generation: {}", amt_perfect);
-
-        // go through and un-sort the burts
-        let mut new_burts: Vec<Burt> = Vec::new();
-        while !bad_burts.is_empty() {
-            let mut current = bad_burts.remove(0);
-
-            // re-educate and mutate the current
-            let best_burt = sorted_burts.get(thread_rng().gen_range(0..sorted_burts.len())).unwrap();
-            // get the best burt
-            let new_mu = best_burt.mu.clone();
-            let new_sigma = best_burt.sigma.clone();
-            // set the new values
-            current.reeducate(new_mu, new_sigma, false);
-            // if the score of the best burt is not 0
-            current.mutate(self.mutation_rate, self.range);
-
-            let mut placed = false;
-            for x in 0..new_burts.len() {
-                let b = new_burts.get(x).unwrap();
-                if current.id < b.id {
-                    new_burts.insert(x, current.clone());
-                    placed = true;
-                    break;
-                }
-            }
-            if !placed {
-                new_burts.push(current);
+    /// appends the best average to the file as "target:{target},mu:{average_mu},sigma:{average_sigma}",
+    /// with a field for every gene of the genome
+    pub fn save_best_average<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let record = self.best_average_record()
+            .ok_or_else(|| invalid_data("The gang has not been trained yet!"))?;
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", record)
+    }
+
+    /// writes the whole gang to a file so training can be resumed with `BurtGang::load`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+
+        writeln!(out, "{}", SAVE_HEADER)?;
+        writeln!(out, "range:{}", self.range)?;
+        writeln!(out, "target:{}", self.target)?;
+        writeln!(out, "generations:{}", self.generations)?;
+        writeln!(out, "current_generation:{}", self.current_generation)?;
+        writeln!(out, "survival_rate:{}", self.survival_rate)?;
+        writeln!(out, "mutation_rate:{}", self.mutation_rate)?;
+        writeln!(out, "seed:{}", self.seed)?;
+        writeln!(out, "selection:{}", self.selection.name())?;
+        writeln!(out, "crossover:{}", self.crossover.name())?;
+        writeln!(out, "crossover_rate:{}", self.crossover_rate)?;
+        writeln!(out, "mutation:{}", self.mutation.name())?;
+        writeln!(out, "elite_count:{}", self.elite_count)?;
+        writeln!(out, "schedule:{}", self.schedule.name())?;
+        writeln!(out, "fitness:{}", self.fitness.name())?;
+        writeln!(out, "samples_per_eval:{}", self.samples_per_eval)?;
+        writeln!(out, "genome:{}", self.genome.name())?;
+        writeln!(out, "boundary:{}", self.boundary)?;
+        writeln!(out, "islands:{}", self.islands.len())?;
+        writeln!(out, "migration:{}", self.migration.name())?;
+        writeln!(out, "sharing:{}", self.sharing.name())?;
+        writeln!(out, "stop_when:{}", stop_conditions_name(&self.stop_when))?;
+        writeln!(out, "strategy:{}", if self.sticky { "sticky" } else { "normal" })?;
+        for (x, island) in self.islands.iter().enumerate() {
+            for (var, value) in island.settings() {
+                writeln!(out, "island:{}:{}:{}", x, var, value)?;
             }
         }
+        writeln!(out, "average_guess:{}", optional_to_string(self.average_guess))?;
+        writeln!(out, "average_score:{}", optional_to_string(self.average_score))?;
+        writeln!(out, "best_score:{}", optional_to_string(self.best_score))?;
+        writeln!(out, "perfect_count:{}", self.perfect_count)?;
+        // only a summary for people reading the file, it is worked out again after loading
+        if let Some(record) = self.best_average_record() {
+            writeln!(out, "best_average:{}", record)?;
+        }
+        for b in &self.burts {
+            writeln!(out, "{}", b.to_record(self.genome.as_ref()))?;
+        }
+
+        out.flush()
+    }
+
+    /// saves the gang to the checkpoint file if a checkpoint is due this generation
+    pub fn checkpoint(&self) -> Option<io::Result<()>> {
+        if self.checkpoint_every == 0 || !self.current_generation.is_multiple_of(self.checkpoint_every) {
+            return None;
+        }
+        Some(self.save(&self.checkpoint_path))
+    }
 
-        while !sorted_burts.is_empty() {
-            let current = sorted_burts.remove(0);
-            // mutate the current
-            //current.mutate(self.mutation_rate, self.range);
+    /// reads a gang written by `BurtGang::save`
+    ///
+    /// the rng is re-seeded from the seed and the generation, so resuming the same file twice
+    /// gives the same run (but not the same run as if training had never stopped)
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
 
-            let mut placed = false;
-            for x in 0..new_burts.len() {
-                let b = new_burts.get(x).unwrap();
-                if current.id < b.id {
-                    new_burts.insert(x, current.clone());
-                    placed = true;
-                    break;
-                }
+        let header = lines.next().transpose()?.unwrap_or_default();
+        if header != SAVE_HEADER {
+            return Err(invalid_data(format!("Not a saved gang: expected \"{}\" but found \"{}\"", SAVE_HEADER, header)));
+        }
+
+        let mut range = None;
+        let mut target = None;
+        let mut generations = None;
+        let mut current_generation = None;
+        let mut survival_rate = None;
+        let mut mutation_rate = None;
+        let mut seed = None;
+        let mut selection: Box<dyn SelectionStrategy> = Box::new(Truncation);
+        let mut crossover: Box<dyn Crossover> = Box::new(BlendCrossover);
+        let mut crossover_rate = 0.0;
+        let mut mutation: Box<dyn Mutation> = Box::new(UniformMutation);
+        let mut elite_count = 0;
+        let mut schedule: Box<dyn TargetSchedule> = Box::new(FixedTarget);
+        let mut fitness: Box<dyn Fitness> = Box::new(AbsoluteError);
+        let mut samples_per_eval = 1;
+        let mut genome: Box<dyn Genome> = Box::new(NormalGenome);
+        let mut boundary = Boundary::default();
+        let mut islands = 1;
+        let mut migration = Migration::default();
+        let mut sharing = Sharing::default();
+        let mut stop_when = Vec::new();
+        let mut sticky = TRAIN_STICKY;
+        // the island settings are applied once every island is there
+        let mut island_settings = Vec::new();
+        let mut average_guess = None;
+        let mut average_score = None;
+        let mut best_score = None;
+        let mut perfect_count = 0;
+        // the burts are read once the genome is known, it says which genes they have
+        let mut records = Vec::new();
+
+        for line in lines {
+            let line = line?;
+            if line.is_empty() {
+                continue;
             }
-            if !placed {
-                new_burts.push(current);
+            let (key, value) = line.split_once(':')
+                .ok_or_else(|| invalid_data(format!("Invalid line: {}", line)))?;
+            match key {
+                "range" => range = Some(parse_value(key, value)?),
+                "target" => target = Some(parse_value(key, value)?),
+                "generations" => generations = Some(parse_value(key, value)?),
+                "current_generation" => current_generation = Some(parse_value(key, value)?),
+                "survival_rate" => survival_rate = Some(parse_value(key, value)?),
+                "mutation_rate" => mutation_rate = Some(parse_value(key, value)?),
+                "seed" => seed = Some(parse_value(key, value)?),
+                "selection" => selection = parse_selection(value).map_err(invalid_data)?,
+                "crossover" => crossover = parse_crossover(value).map_err(invalid_data)?,
+                "crossover_rate" => crossover_rate = parse_value(key, value)?,
+                "mutation" => mutation = parse_mutation(value).map_err(invalid_data)?,
+                "elite_count" => elite_count = parse_value(key, value)?,
+                "schedule" => schedule = parse_schedule(value).map_err(invalid_data)?,
+                "fitness" => fitness = parse_fitness(value).map_err(invalid_data)?,
+                "samples_per_eval" => samples_per_eval = parse_value(key, value)?,
+                "genome" => genome = parse_genome(value).map_err(invalid_data)?,
+                "boundary" => boundary = value.parse().map_err(invalid_data)?,
+                "islands" => islands = parse_value(key, value)?,
+                "migration" => migration = parse_migration(value).map_err(invalid_data)?,
+                "sharing" => sharing = parse_sharing(value).map_err(invalid_data)?,
+                "stop_when" => stop_when = parse_stop_conditions(value).map_err(invalid_data)?,
+                "strategy" => sticky = value.parse::<Strategy>().map_err(invalid_data)?.is_sticky(),
+                "island" => island_settings.push(parse_island_setting(value).map_err(invalid_data)?),
+                "average_guess" => average_guess = parse_optional(key, value)?,
+                "average_score" => average_score = parse_optional(key, value)?,
+                "best_score" => best_score = parse_optional(key, value)?,
+                "perfect_count" => perfect_count = parse_value(key, value)?,
+                "best_average" => {}
+                "burt" => records.push(value.to_string()),
+                _ => return Err(invalid_data(format!("Unknown key: {}", key))),
             }
         }
-        self.burts = new_burts;
+
+        let burts = records.iter()
+            .map(|record| Burt::from_record(record, genome.as_ref()))
+            .collect::<io::Result<Vec<Burt>>>()?;
+
+        let missing = |key: &str| invalid_data(format!("The saved gang is missing {}", key));
+        let seed: u64 = seed.ok_or_else(|| missing("seed"))?;
+        let current_generation: u32 = current_generation.ok_or_else(|| missing("current_generation"))?;
+        let range = range.ok_or_else(|| missing("range"))?;
+        let target = target.ok_or_else(|| missing("target"))?;
+        let survival_rate = survival_rate.ok_or_else(|| missing("survival_rate"))?;
+        let mutation_rate = mutation_rate.ok_or_else(|| missing("mutation_rate"))?;
+
+        // the same checks as the command line, a hand edited file shouldn't be able to panic the training
+        check_range(range).map_err(invalid_data)?;
+        check_target(target, range).map_err(invalid_data)?;
+        check_rate("survival rate", survival_rate).map_err(invalid_data)?;
+        check_rate("mutation rate", mutation_rate).map_err(invalid_data)?;
+        check_crossover_rate(crossover_rate).map_err(invalid_data)?;
+        check_samples_per_eval(samples_per_eval).map_err(invalid_data)?;
+        check_islands(islands).map_err(invalid_data)?;
+        check_burt_count(burts.len() as u32).map_err(invalid_data)?;
+        // the islands and the UI find burts by where they are, so burt x has to be the xth one
+        if let Some((x, b)) = burts.iter().enumerate().find(|(x, b)| b.id as usize != *x) {
+            return Err(invalid_data(format!("The burts are out of order: burt {} is number {}", b.id, x)));
+        }
+
+        let mut burt_gang = BurtGang::new(range, target,
+                                          generations.ok_or_else(|| missing("generations"))?,
+                                          survival_rate, mutation_rate, seed);
+        burt_gang.current_generation = current_generation;
+        burt_gang.selection = selection;
+        burt_gang.crossover = crossover;
+        burt_gang.crossover_rate = crossover_rate;
+        burt_gang.mutation = mutation;
+        burt_gang.elite_count = elite_count;
+        burt_gang.schedule = schedule;
+        burt_gang.fitness = fitness;
+        burt_gang.samples_per_eval = samples_per_eval;
+        burt_gang.genome = genome;
+        burt_gang.boundary = boundary;
+        burt_gang.set_islands(islands);
+        burt_gang.migration = migration;
+        burt_gang.sharing = sharing;
+        burt_gang.stop_when = stop_when;
+        burt_gang.sticky = sticky;
+        for (island, var, value) in island_settings {
+            burt_gang.set_island(island, &var, &value).map_err(invalid_data)?;
+        }
+        burt_gang.rng = StdRng::seed_from_u64(seed.wrapping_add(current_generation as u64));
+        burt_gang.average_guess = average_guess;
+        burt_gang.average_score = average_score;
+        burt_gang.best_score = best_score;
+        burt_gang.perfect_count = perfect_count;
+        burt_gang.burts = burts;
+
+        Ok(burt_gang)
     }
 
-    pub fn save_best_average(&self) {
-        // todo(eric): Write the best of the average mu's and sigma's to a file along with the guess
-        //  i.e. in "target:{target},mu:{average_mu},sigma:{average_sigma}"
+    pub fn average_guess(&self) -> Option<u32> {
+        self.average_guess
     }
 
-    pub fn av_guess_display(&self) -> String {
-        format!("{}", if self.average_guess.is_some() {
-            format!("{}", self.average_guess.unwrap())
-        } else {
-            format!("?")
-        })
+    pub fn average_score(&self) -> Option<u32> {
+        self.average_score
     }
 
-    pub fn av_score_display(&self) -> String {
-        format!("{}", if self.average_score.is_some() {
-            format!("{}", self.average_score.unwrap())
-        } else {
-            format!("?")
-        })
+    pub fn best_score(&self) -> Option<u32> {
+        self.best_score
+    }
+
+    pub fn perfect_count(&self) -> u32 {
+        self.perfect_count
+    }
+
+    /// the stats of every generation trained since the gang was made or loaded
+    pub fn history(&self) -> &[GenerationStats] {
+        &self.history
+    }
+
+    /// the settings every island trains with, the gang's where the island has none of its own
+    pub fn island_settings(&self) -> Vec<IslandSettings> {
+        let count = self.islands.len();
+        self.islands.iter().enumerate().map(|(x, island)| IslandSettings {
+            burts: island_range(x, count, self.burts.len()).len(),
+            survival_rate: self.survival_rate_of(x),
+            mutation_rate: self.mutation_rate_of(x),
+            selection: island.selection.as_ref().unwrap_or(&self.selection).name(),
+        }).collect()
+    }
+
+    /// why training should stop before the last generation, if any of the stop conditions is met.
+    /// `trained_for` is how long the gang has been training, which the gang can't know itself
+    pub fn stop_reason(&self, trained_for: Duration) -> Option<String> {
+        self.stop_when.iter().find_map(|condition| condition.check(&self.stop_progress, trained_for))
+    }
+
+    /// how each island did in the last generation, empty until the islands have trained
+    pub fn island_stats(&self) -> &[IslandStats] {
+        &self.island_stats
     }
 }
 
@@ -483,7 +1158,20 @@ impl Display for BurtGang {
                          format!("Generations:   {}", self.generations),
                          format!("Survival rate: {}", self.survival_rate),
                          format!("Mutation rate: {}", self.mutation_rate),
-                         format!("# of burts:    {}", self.burts.len())];
+                         format!("Selection:     {}", self.selection.name()),
+                         format!("Crossover:     {} ({})", self.crossover.name(), self.crossover_rate),
+                         format!("Mutation:      {}", self.mutation.name()),
+                         format!("Elites:        {}", self.elite_count),
+                         format!("Schedule:      {}", self.schedule.name()),
+                         format!("Fitness:       {}", self.fitness.name()),
+                         format!("Samples:       {}", self.samples_per_eval),
+                         format!("Genome:        {}", self.genome.name()),
+                         format!("Boundary:      {}", self.boundary),
+                         format!("Islands:       {} (migration: {})", self.islands.len(), self.migration.name()),
+                         format!("Sharing:       {}", self.sharing.name()),
+                         format!("Stop when:     {}", stop_conditions_name(&self.stop_when)),
+                         format!("# of burts:    {}", self.burts.len()),
+                         format!("Seed:          {}", self.seed)];
 
         // get the longest line
         let mut longest = 0;
@@ -506,22 +1194,24 @@ impl Display for BurtGang {
     }
 }
 
-pub fn populate_burts(burt_count: u32, range: u32, display: bool) -> Vec<Burt> {
+pub fn populate_burts(burt_count: u32, genome: &dyn Genome, range: u32, display: bool, rng: &mut StdRng) -> Vec<Burt> {
     let mut burts = Vec::new();
     // print the progress bar and begin populating Burts
+    // (the bar asks the terminal for the cursor position, so only make it when displaying)
+    let mut pbar = None;
     if display {
         println!("Populating Burts...");
+        hide_cursor();
+        pbar = Some(PBar::new_at_cursor(BarType::Bar, true, true, 20)
+            .expect("Failed to get cursor position: is this terminal supported?"));
     }
-    hide_cursor();
-    let mut pbar = PBar::new_at_cursor(BarType::Bar, true, true, 20)
-        .expect("Failed to get cursor position: is this terminal supported?");
     // make each new burt with x being their id, and update the progress bar
     for x in 0..burt_count {
         // push the new burt into the vector
-        burts.push(Burt::new(x, range.clone()));
+        burts.push(Burt::new(x, genome, range, rng));
 
         // get the percentage of completion
-        if display {
+        if let Some(pbar) = &mut pbar {
             let percent = x as f64 / (burt_count - 1) as f64;
             pbar.update((percent * 100.0) as u8);
             pbar.draw();
@@ -529,20 +1219,69 @@ pub fn populate_burts(burt_count: u32, range: u32, display: bool) -> Vec<Burt> {
             println!("Burts: {} / {}", x + 1, burt_count);
         }
     }
-    // clear the output styles
-    flush_styles();
-    // print out completion message and show the cursor
+    // clear the output styles, print out completion message and show the cursor
     if display {
+        flush_styles();
         println!("\nPopulated {} Burts!", burt_count);
+        show_cursor();
     }
-    show_cursor();
     burts
 }
 
-pub fn get_burt_gang() -> BurtGang {
-    // get the terminal size for displaying
-    let (term_width, term_height) = crossterm::terminal::size().expect("Failed to get terminal size");
+// the checks below are shared by the interactive prompts and the command line arguments
+
+pub fn check_range(range: u32) -> Result<(), String> {
+    if range < 1 {
+        return Err(String::from("The range can not be zero!"));
+    }
+    Ok(())
+}
+
+pub fn check_target(target: u32, range: u32) -> Result<(), String> {
+    if !(0..range).contains(&target) {
+        return Err(format!("The target must be within the range! Range: 0 to {}", range));
+    }
+    Ok(())
+}
+
+pub fn check_rate(name: &str, rate: f32) -> Result<(), String> {
+    // written this way around so NaN is rejected as well
+    if !(rate > 0.0 && rate < 1.0) {
+        return Err(format!("The {} must be between 0 and 1, not including 0 and 1.", name));
+    }
+    Ok(())
+}
+
+pub fn check_crossover_rate(rate: f32) -> Result<(), String> {
+    // unlike the other rates, crossing over never or always are both fine
+    if !(0.0..=1.0).contains(&rate) {
+        return Err(String::from("The crossover rate must be between 0 and 1."));
+    }
+    Ok(())
+}
 
+pub fn check_samples_per_eval(samples: u32) -> Result<(), String> {
+    if samples == 0 {
+        return Err(String::from("Every burt has to be evaluated at least once!"));
+    }
+    Ok(())
+}
+
+pub fn check_islands(islands: usize) -> Result<(), String> {
+    if islands == 0 {
+        return Err(String::from("There has to be at least 1 island!"));
+    }
+    Ok(())
+}
+
+pub fn check_burt_count(burt_count: u32) -> Result<(), String> {
+    if burt_count == 0 {
+        return Err(String::from("There has to be at least 1 burt!"));
+    }
+    Ok(())
+}
+
+pub fn get_burt_gang(seed: u64) -> BurtGang {
     // the input variables
     let mut range: u32;
     let mut target: u32;
@@ -560,21 +1299,11 @@ pub fn get_burt_gang() -> BurtGang {
         }
         range = input.unwrap();
         // if it is 0, error and try again
-        if range < 1 {
-            println!("{}Warning: The range can not be zero!", Color::Yellow);
+        if let Err(e) = check_range(range) {
+            println!("{}Warning: {}", Color::Yellow, e);
             flush_styles();
             continue;
         }
-        // if the range is greater than the terminal width, warn the user and ask if they want to change the range
-        if range > term_width as u32 {
-            println!("{}Warning: The range is greater than the current terminal width! this can cause rendering issues!\n\
-                    Current terminal size: {}x{}", Color::Yellow, term_width, term_height);
-            flush_styles();
-            let new_value = prompt("Are you sure you wish to use this number?");
-            if !new_value {
-                continue;
-            }
-        }
         break
     }
     loop {
@@ -585,8 +1314,8 @@ pub fn get_burt_gang() -> BurtGang {
         }
         target = input.unwrap();
         // if the target is not in the range, ask for a new number
-        if !(0..range).contains(&target) {
-            println!("{}Warning: The target must be within the range! Range: 0 to {}", Color::Yellow, range);
+        if let Err(e) = check_target(target, range) {
+            println!("{}Warning: {}", Color::Yellow, e);
             flush_styles();
         } else {
             break;
@@ -619,8 +1348,8 @@ pub fn get_burt_gang() -> BurtGang {
         }
         survival_rate = input.unwrap();
         // if it is 1, 0, or outside that range, get a different number
-        if survival_rate >= 1.0 || survival_rate <= 0.0 {
-            println!("{}Warning: The survival rate must be between 0 and 1, not including 0 and 1.", Color::Yellow);
+        if let Err(e) = check_rate("survival rate", survival_rate) {
+            println!("{}Warning: {}", Color::Yellow, e);
             flush_styles();
         } else {
             break;
@@ -634,8 +1363,8 @@ pub fn get_burt_gang() -> BurtGang {
         }
         mutation_rate = input.unwrap();
         // if it is 1, 0, or outside that range, get a different number
-        if mutation_rate >= 1.0 || mutation_rate <= 0.0 {
-            println!("{}Warning: The mutation rate must be between 0 and 1, not including 0 and 1.", Color::Yellow);
+        if let Err(e) = check_rate("mutation rate", mutation_rate) {
+            println!("{}Warning: {}", Color::Yellow, e);
             flush_styles();
         } else {
             break;
@@ -650,8 +1379,8 @@ pub fn get_burt_gang() -> BurtGang {
         burt_count = input.unwrap();
 
         // can't process with less than 1 burt
-        if burt_count == 0 {
-            println!("{}Warning: There has to be at least 1 burt!", Color::Yellow);
+        if let Err(e) = check_burt_count(burt_count) {
+            println!("{}Warning: {}", Color::Yellow, e);
             flush_styles();
             continue;
         }
@@ -668,5 +1397,316 @@ pub fn get_burt_gang() -> BurtGang {
         break;
     }
 
-    BurtGang::new(populate_burts(burt_count, range.clone(), true), range, target, generations, survival_rate, mutation_rate)
-}
\ No newline at end of file
+    let mut burt_gang = BurtGang::new(range, target, generations, survival_rate, mutation_rate, seed);
+    burt_gang.populate(burt_count, true);
+    burt_gang
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    extern crate test;
+
+    use super::*;
+
+    // a small gang of 60 burts guessing 7 out of 100, the same for the same seed. the tests of the other modules use it too
+    pub(crate) fn seeded_gang(seed: u64) -> BurtGang {
+        let mut burt_gang = BurtGang::new(100, 7, 30, 0.25, 0.01, seed);
+        burt_gang.populate(60, false);
+        burt_gang
+    }
+
+    #[test]
+    fn same_seed_same_run() {
+        for advanced in [false, true] {
+            let mut a = seeded_gang(42);
+            let mut b = seeded_gang(42);
+            assert_eq!(a.burts, b.burts);
+
+            for _ in 0..a.generations {
+                a.train(advanced);
+                b.train(advanced);
+                assert_eq!(a.burts, b.burts);
+                assert_eq!(a.average_guess, b.average_guess);
+                assert_eq!(a.average_score, b.average_score);
+            }
+        }
+    }
+
+    #[test]
+    fn every_selection_strategy_trains() {
+        // 60 burts from best to worst, a quarter of them surviving. picking parents at random would average index 29.5
+        let scores: Vec<u32> = (0..60).collect();
+        let mut rng = StdRng::seed_from_u64(3);
+        for spec in ["truncation", "tournament:2", "roulette", "rank"] {
+            let selection = parse_selection(spec).unwrap();
+            assert_eq!(selection.name(), spec);
+            let parents = selection.select(&scores, 15, 6000, &mut rng);
+            assert!(parents.iter().all(|x| *x < 60));
+            let mean = parents.iter().sum::<usize>() as f32 / parents.len() as f32;
+            assert!(mean < 25.0, "{} picks parents with a mean index of {}", spec, mean);
+
+            let mut burt_gang = seeded_gang(3);
+            burt_gang.selection = selection;
+            for _ in 0..5 {
+                burt_gang.train(false);
+                burt_gang.train(true);
+            }
+            assert_eq!(burt_gang.burts.len(), 60);
+            let (first, last) = (&burt_gang.history()[0], burt_gang.history().last().unwrap());
+            assert!(last.mean_score < first.mean_score, "{}: {} to {}", spec, first.mean_score, last.mean_score);
+        }
+        assert!(parse_selection("tournament:0").is_err());
+        assert!(parse_selection("best").is_err());
+    }
+
+    #[test]
+    fn elites_are_untouched() {
+        for advanced in [false, true] {
+            let mut burt_gang = seeded_gang(11);
+            burt_gang.survival_rate = 0.01;
+            burt_gang.elite_count = 5;
+            burt_gang.train(advanced);
+
+            let before = burt_gang.burts.clone();
+            burt_gang.train(advanced);
+
+            // ties can be broken either way, so look at every burt at least as good as the 5th best
+            let mut scores: Vec<u32> = burt_gang.iter().map(|b| b.score.unwrap()).collect();
+            scores.sort();
+            let untouched = burt_gang.iter()
+                .zip(&before)
+                .filter(|(b, old)| b.score.unwrap() <= scores[4] && b.genes() == old.genes())
+                .count();
+            assert!(untouched >= 5);
+        }
+    }
+
+    #[test]
+    fn perfect_burts_are_spared() {
+        // sharing ranks a crowd of perfect burts below the burts on their own, they still have to be kept
+        let mut burt_gang = seeded_gang(13);
+        burt_gang.sharing = parse_sharing("2").unwrap();
+        let mut spared = 0;
+        for _ in 0..burt_gang.generations {
+            let before = burt_gang.burts.clone();
+            burt_gang.train(true);
+            for (b, old) in burt_gang.iter().zip(&before) {
+                if b.score == Some(0) {
+                    assert_eq!(b.genes(), old.genes(), "perfect burt {} was replaced", b.id);
+                    spared += 1;
+                }
+            }
+        }
+        assert!(spared > 0);
+    }
+
+    #[test]
+    fn save_load_round_trip() {
+        let mut burt_gang = seeded_gang(7);
+        burt_gang.selection = parse_selection("tournament:4").unwrap();
+        burt_gang.crossover = parse_crossover("blx:0.25").unwrap();
+        burt_gang.crossover_rate = 0.5;
+        burt_gang.mutation = parse_mutation("gene:0.5:adaptive").unwrap();
+        burt_gang.elite_count = 3;
+        burt_gang.schedule = parse_schedule("cycle:3,9,27").unwrap();
+        burt_gang.fitness = parse_fitness("samples:3:band:2").unwrap();
+        burt_gang.samples_per_eval = 4;
+        burt_gang.boundary = Boundary::Reflect;
+        burt_gang.set_islands(3);
+        burt_gang.migration = parse_migration("random:2:3").unwrap();
+        burt_gang.set_island(1, "mutation_rate", "0.2").unwrap();
+        burt_gang.set_island(2, "selection", "rank").unwrap();
+        burt_gang.sharing = parse_sharing("2.5:2").unwrap();
+        burt_gang.stop_when = parse_stop_conditions("perfect:5,time:2m").unwrap();
+        burt_gang.sticky = true;
+        for _ in 0..5 {
+            burt_gang.train(false);
+        }
+
+        let path = std::env::temp_dir().join(format!("malb_round_trip_{}.txt", std::process::id()));
+        burt_gang.save(&path).unwrap();
+        let loaded = BurtGang::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.burts, burt_gang.burts);
+        assert_eq!(loaded.range, burt_gang.range);
+        assert_eq!(loaded.target, burt_gang.target);
+        assert_eq!(loaded.generations, burt_gang.generations);
+        assert_eq!(loaded.current_generation, burt_gang.current_generation);
+        assert_eq!(loaded.survival_rate, burt_gang.survival_rate);
+        assert_eq!(loaded.mutation_rate, burt_gang.mutation_rate);
+        assert_eq!(loaded.seed, burt_gang.seed);
+        assert_eq!(loaded.selection.name(), burt_gang.selection.name());
+        assert_eq!(loaded.crossover.name(), burt_gang.crossover.name());
+        assert_eq!(loaded.crossover_rate, burt_gang.crossover_rate);
+        assert_eq!(loaded.mutation.name(), burt_gang.mutation.name());
+        assert_eq!(loaded.elite_count, burt_gang.elite_count);
+        assert_eq!(loaded.schedule.name(), burt_gang.schedule.name());
+        assert_eq!(loaded.fitness.name(), burt_gang.fitness.name());
+        assert_eq!(loaded.samples_per_eval, burt_gang.samples_per_eval);
+        assert_eq!(loaded.boundary, burt_gang.boundary);
+        assert_eq!(loaded.island_settings(), burt_gang.island_settings());
+        assert_eq!(loaded.migration, burt_gang.migration);
+        assert_eq!(loaded.sharing, burt_gang.sharing);
+        assert_eq!(loaded.stop_when, burt_gang.stop_when);
+        assert!(loaded.sticky);
+        assert_eq!(loaded.average_guess, burt_gang.average_guess);
+        assert_eq!(loaded.average_score, burt_gang.average_score);
+        assert_eq!(loaded.best_score, burt_gang.best_score);
+        assert_eq!(loaded.perfect_count, burt_gang.perfect_count);
+        assert_eq!(loaded.best_average(), burt_gang.best_average());
+    }
+
+    #[test]
+    fn settings_copy_starts_over() {
+        let mut burt_gang = BurtGang::new(100, 7, 30, 0.25, 0.01, 11);
+        burt_gang.genome = parse_genome("linear").unwrap();
+        burt_gang.populate(60, false);
+        burt_gang.selection = parse_selection("rank").unwrap();
+        burt_gang.elite_count = 2;
+        burt_gang.checkpoint_every = 5;
+        burt_gang.set_islands(4);
+        burt_gang.set_island(3, "survival_rate", "0.5").unwrap();
+        burt_gang.stop_when = parse_stop_conditions("stagnant:9").unwrap();
+        let before = burt_gang.burts.clone();
+        for _ in 0..5 {
+            burt_gang.train(false);
+        }
+
+        let mut copy = burt_gang.settings_copy();
+        assert!(copy.burts.is_empty());
+        assert_eq!(copy.current_generation, 0);
+        assert!(copy.history().is_empty());
+        assert_eq!(copy.genome.name(), "linear");
+        assert_eq!(copy.selection.name(), "rank");
+        assert_eq!(copy.elite_count, 2);
+        assert_eq!(copy.checkpoint_every, 5);
+        assert_eq!(copy.stop_when, burt_gang.stop_when);
+
+        // the same seed, so the new burts are the ones the run started with
+        copy.populate(60, false);
+        assert_eq!(copy.burts, before);
+        assert_eq!(copy.island_settings(), burt_gang.island_settings());
+    }
+
+    #[test]
+    fn load_rejects_other_files() {
+        let path = std::env::temp_dir().join(format!("malb_not_a_gang_{}.txt", std::process::id()));
+        std::fs::write(&path, "range:100\n").unwrap();
+        assert!(BurtGang::load(&path).is_err());
+
+        // a gang with values the command line wouldn't take is rejected instead of panicking later on
+        seeded_gang(7).save(&path).unwrap();
+        let saved = std::fs::read_to_string(&path).unwrap();
+        for (key, bad) in [("crossover_rate", "2"), ("survival_rate", "5"), ("mutation_rate", "0"), ("target", "100"),
+                           ("range", "0"), ("samples_per_eval", "0"), ("islands", "0")] {
+            let edited: Vec<String> = saved.lines()
+                .map(|line| if line.split_once(':').is_some_and(|(k, _)| k == key) { format!("{}:{}", key, bad) } else { line.to_string() })
+                .collect();
+            std::fs::write(&path, edited.join("\n")).unwrap();
+            let error = BurtGang::load(&path).err().unwrap_or_else(|| panic!("{}:{} was loaded", key, bad));
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+
+        // a gang without burts never trains, and burts that aren't in id order can't be found
+        let burts: Vec<&str> = saved.lines().filter(|line| line.starts_with("burt:")).collect();
+        let others: Vec<&str> = saved.lines().filter(|line| !line.starts_with("burt:")).collect();
+        let swapped = [&others[..], &[burts[1], burts[0]], &burts[2..]].concat();
+        for edited in [others.clone(), swapped] {
+            std::fs::write(&path, edited.join("\n")).unwrap();
+            assert_eq!(BurtGang::load(&path).err().map(|e| e.kind()), Some(io::ErrorKind::InvalidData));
+        }
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn different_seed_different_run() {
+        assert_ne!(seeded_gang(1).burts, seeded_gang(2).burts);
+    }
+
+    #[test]
+    fn burts_stay_in_id_order() {
+        for advanced in [false, true] {
+            let mut burt_gang = seeded_gang(5);
+            for _ in 0..10 {
+                burt_gang.train(advanced);
+            }
+            assert!(burt_gang.iter().enumerate().all(|(x, b)| b.get_id() == x as u32));
+        }
+    }
+
+    #[test]
+    fn same_run_on_any_number_of_threads() {
+        // more burts than one chunk so the work is actually split up
+        let train = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new().num_threads(threads).build().unwrap();
+            let mut burt_gang = BurtGang::new(100, 7, 30, 0.25, 0.01, 3);
+            burt_gang.populate(3 * CHUNK_SIZE as u32, false);
+            burt_gang.crossover_rate = 0.5;
+            pool.install(|| for _ in 0..5 {
+                burt_gang.train(false);
+            });
+            burt_gang
+        };
+        let (one, four) = (train(1), train(4));
+        assert_eq!(one.burts, four.burts);
+        assert_eq!(one.history(), four.history());
+    }
+
+    #[test]
+    fn records_every_generation() {
+        let mut burt_gang = seeded_gang(11);
+        for _ in 0..10 {
+            burt_gang.train(false);
+            let stats = burt_gang.history().last().unwrap();
+            assert_eq!(stats.generation, burt_gang.current_generation);
+            assert_eq!(Some(stats.min_score), burt_gang.best_score());
+            assert_eq!(stats.perfect_count, burt_gang.perfect_count());
+            assert!(stats.min_score <= stats.median_score && stats.median_score <= stats.max_score);
+            assert!(stats.min_score as f32 <= stats.mean_score && stats.mean_score <= stats.max_score as f32);
+        }
+        assert_eq!(burt_gang.history().len(), 10);
+    }
+
+    #[test]
+    fn samples_per_eval_scores_the_mean() {
+        // a single evaluation can't vary
+        let mut burt_gang = seeded_gang(5);
+        burt_gang.train(false);
+        for b in burt_gang.iter() {
+            assert_eq!(b.best_score, b.score);
+            assert_eq!(b.score_variance, Some(0.0));
+        }
+
+        let mut burt_gang = seeded_gang(5);
+        burt_gang.samples_per_eval = 8;
+        burt_gang.train(false);
+        assert!(burt_gang.iter().all(|b| b.best_score <= b.score && b.score_variance.unwrap() >= 0.0));
+        // the gang starts with wide sigmas, so some burts must have scored differently across evaluations
+        assert!(burt_gang.iter().any(|b| b.score_variance.unwrap() > 0.0));
+
+        // a burt that always guesses the same number always gets the same score
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut burt = Burt::new(0, &NormalGenome, 100, &mut rng);
+        burt.set_genes(&[10.0, 0.0]);
+        let evaluation = Evaluation {
+            examples: &[Example::target(7)],
+            fitness: &AbsoluteError,
+            samples: 5,
+            genome: &NormalGenome,
+            boundary: Boundary::Truncate,
+            range: 100,
+        };
+        burt.training_think(&evaluation, &mut rng);
+        assert_eq!((burt.score, burt.best_score, burt.score_variance), (Some(3), Some(3), Some(0.0)));
+    }
+
+    // a generation of a million burts is too slow for every test run, run it with cargo +nightly bench -- --ignored
+    #[bench]
+    #[ignore]
+    fn million_burt_generation(b: &mut test::Bencher) {
+        let mut burt_gang = BurtGang::new(1000, 7, u32::MAX, 0.25, 0.01, 1);
+        burt_gang.populate(1_000_000, false);
+        b.iter(|| burt_gang.train(false));
+    }
+}
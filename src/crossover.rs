@@ -0,0 +1,99 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::burt::split_spec;
+
+/// mixes the genes of two parents into the genes of a child
+pub trait Crossover: Send + Sync {
+    fn name(&self) -> String;
+
+    /// `a` and `b` are the same length, and so is the returned child. the genes of the parents are between 0 and `range`
+    fn cross(&self, a: &[f32], b: &[f32], range: f32, rng: &mut StdRng) -> Vec<f32>;
+}
+
+/// every gene comes from either parent with the same chance
+pub struct UniformCrossover;
+
+impl Crossover for UniformCrossover {
+    fn name(&self) -> String {
+        String::from("uniform")
+    }
+
+    fn cross(&self, a: &[f32], b: &[f32], _range: f32, rng: &mut StdRng) -> Vec<f32> {
+        a.iter().zip(b).map(|(a, b)| if rng.gen_bool(0.5) { *a } else { *b }).collect()
+    }
+}
+
+/// a weighted average of the parents, with one random weight for the whole child
+pub struct BlendCrossover;
+
+impl Crossover for BlendCrossover {
+    fn name(&self) -> String {
+        String::from("blend")
+    }
+
+    fn cross(&self, a: &[f32], b: &[f32], _range: f32, rng: &mut StdRng) -> Vec<f32> {
+        let weight: f32 = rng.gen_range(0.0..=1.0);
+        a.iter().zip(b).map(|(a, b)| weight * a + (1.0 - weight) * b).collect()
+    }
+}
+
+// past this the children are all but random anyway
+const MAX_BLX_ALPHA: f32 = 10.0;
+
+/// BLX-alpha, every gene is picked from the parents' interval stretched by alpha on both sides
+pub struct BlxAlpha {
+    pub alpha: f32,
+}
+
+impl Crossover for BlxAlpha {
+    fn name(&self) -> String {
+        format!("blx:{}", self.alpha)
+    }
+
+    fn cross(&self, a: &[f32], b: &[f32], range: f32, rng: &mut StdRng) -> Vec<f32> {
+        a.iter().zip(b).map(|(a, b)| {
+            let (low, high) = if a < b { (*a, *b) } else { (*b, *a) };
+            let stretch = (high - low) * self.alpha;
+            // the stretched interval is cut down to the range, so even a broken gene can't make an interval to panic on
+            let low = (low - stretch).max(0.0).min(range);
+            let high = (high + stretch).min(range).max(low);
+            rng.gen_range(low..=high)
+        }).collect()
+    }
+}
+
+/// parses a crossover operator from "uniform", "blend" or "blx[:alpha]"
+pub fn parse_crossover(spec: &str) -> Result<Box<dyn Crossover>, String> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let (name, arg) = split_spec(&spec);
+
+    match (name, arg) {
+        ("uniform", None) => Ok(Box::new(UniformCrossover)),
+        ("blend", None) => Ok(Box::new(BlendCrossover)),
+        ("blx", None) => Ok(Box::new(BlxAlpha { alpha: 0.5 })),
+        ("blx", Some(alpha)) => match alpha.parse::<f32>() {
+            Ok(alpha) if (0.0..=MAX_BLX_ALPHA).contains(&alpha) => Ok(Box::new(BlxAlpha { alpha })),
+            _ => Err(format!("The BLX alpha must be a number from 0 to {}!", MAX_BLX_ALPHA)),
+        },
+        _ => Err(format!("Unknown crossover: {} (expected uniform, blend or blx[:alpha])", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn blx_stays_in_the_range() {
+        for bad in ["blx:inf", "blx:1e38", "blx:NaN", "blx:-1", "blx:10.5"] {
+            assert!(parse_crossover(bad).is_err(), "{} should be rejected", bad);
+        }
+        let blx = parse_crossover("blx:10").unwrap();
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let child = blx.cross(&[10.0, f32::NAN, 50.0], &[90.0, 30.0, f32::INFINITY], 100.0, &mut rng);
+            assert!(child.iter().all(|gene| (0.0..=100.0).contains(gene)), "{:?}", child);
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use rayon::prelude::*;
+use crate::burt::Burt;
+use crate::island::island_of;
+
+/// fitness sharing: a burt's score is made worse the more burts share its niche, so a crowd with the same genes
+/// loses out to the burts trying something else. the niches are the cells of a grid over the genes that are
+/// `radius` wide, counted per island
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sharing {
+    // 0 turns sharing off
+    pub radius: f32,
+    // how hard crowding is punished, the score is scaled by the size of the niche to the power of alpha
+    pub alpha: f32,
+}
+
+impl Default for Sharing {
+    fn default() -> Self {
+        Self { radius: 0.0, alpha: 1.0 }
+    }
+}
+
+impl Sharing {
+    pub fn is_on(&self) -> bool {
+        self.radius > 0.0
+    }
+
+    /// "none" when sharing is off, otherwise "radius:alpha"
+    pub fn name(&self) -> String {
+        if !self.is_on() {
+            return String::from("none");
+        }
+        format!("{}:{}", self.radius, self.alpha)
+    }
+
+    /// the score a burt is ranked on in a niche of `niche_size` burts. one is added first,
+    /// so a crowd of perfect burts is pushed down as well
+    pub fn shared_score(&self, score: u32, niche_size: u32) -> u32 {
+        let shared = (score as f64 + 1.0) * (niche_size.max(1) as f64).powf(self.alpha as f64) - 1.0;
+        shared.round().min(u32::MAX as f64) as u32
+    }
+}
+
+/// parses sharing from "none" or "radius[:alpha]"
+pub fn parse_sharing(spec: &str) -> Result<Sharing, String> {
+    let spec = spec.trim().to_ascii_lowercase().replace(' ', ":");
+    if spec == "none" {
+        return Ok(Sharing::default());
+    }
+    let mut parts = spec.split(':');
+    let radius = match parts.next().map(|radius| radius.parse::<f32>()) {
+        Some(Ok(radius)) if radius > 0.0 && radius.is_finite() => radius,
+        _ => return Err(format!("Invalid sharing: {} (expected none or radius[:alpha] with a radius above 0)", spec)),
+    };
+    let alpha = match parts.next().map(|alpha| alpha.parse::<f32>()) {
+        None => 1.0,
+        Some(Ok(alpha)) if alpha > 0.0 && alpha.is_finite() => alpha,
+        _ => return Err(String::from("The sharing alpha must be above 0!")),
+    };
+    if parts.next().is_some() {
+        return Err(format!("Invalid sharing: {}", spec));
+    }
+    Ok(Sharing { radius, alpha })
+}
+
+/// how many burts of the same island share each burt's cell of the gene grid, in the order of the burts
+pub fn niche_sizes(burts: &[Burt], islands: usize, radius: f32) -> Vec<u32> {
+    let cells: Vec<(usize, Vec<i64>)> = burts.par_iter()
+        .enumerate()
+        .map(|(x, b)| {
+            let cell = b.genes().iter().map(|gene| (gene / radius).floor() as i64).collect();
+            (island_of(x, islands, burts.len()), cell)
+        })
+        .collect();
+    let mut sizes: HashMap<&(usize, Vec<i64>), u32> = HashMap::new();
+    for cell in &cells {
+        *sizes.entry(cell).or_default() += 1;
+    }
+    cells.iter().map(|cell| sizes[cell]).collect()
+}
+
+/// how many different sets of genes there are among the burts
+pub fn unique_genomes(burts: &[Burt]) -> u32 {
+    // comparing hashes instead of the genes themselves, two different genomes having the same hash is next to impossible
+    let mut hashes: Vec<u64> = burts.par_iter()
+        .map(|b| {
+            let mut hasher = DefaultHasher::new();
+            for gene in b.genes() {
+                gene.to_bits().hash(&mut hasher);
+            }
+            hasher.finish()
+        })
+        .collect();
+    hashes.par_sort_unstable();
+    hashes.dedup();
+    hashes.len() as u32
+}
+
+/// the root mean square distance between the genes of two different burts, worked out from the spread of every gene
+/// (the mean squared distance of all pairs is twice the total variance) so it doesn't have to compare every pair
+pub fn rms_distance(gene_stddevs: &[f32], count: usize) -> f32 {
+    if count < 2 {
+        return 0.0;
+    }
+    let variance: f64 = gene_stddevs.iter().map(|stddev| *stddev as f64 * *stddev as f64).sum();
+    (2.0 * variance * count as f64 / (count - 1) as f64).sqrt() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+
+    #[test]
+    fn sharing_keeps_the_burts_diverse() {
+        let mut plain = seeded_gang(19);
+        let mut shared = seeded_gang(19);
+        shared.sharing = parse_sharing("5").unwrap();
+        // without mutation the children are copies, so the genomes can only get fewer
+        plain.mutation_rate = 0.0;
+        shared.mutation_rate = 0.0;
+        for _ in 0..plain.generations {
+            plain.train(false);
+            shared.train(false);
+        }
+
+        // every burt starts out with its own genes, and without sharing they quickly settle on a few
+        let (first, last) = (&plain.history()[0], plain.history().last().unwrap());
+        assert_eq!(first.unique_genomes, 60);
+        assert!(last.unique_genomes < first.unique_genomes);
+        assert!(last.rms_distance < first.rms_distance);
+
+        let shared_last = shared.history().last().unwrap();
+        assert!(shared_last.unique_genomes > last.unique_genomes, "{} vs {}", shared_last.unique_genomes, last.unique_genomes);
+        assert!(shared_last.rms_distance > last.rms_distance);
+        // the stats are of the real scores either way
+        assert_eq!(shared_last.min_score, shared.best_score().unwrap());
+
+        let sharing = parse_sharing("2:1").unwrap();
+        assert_eq!(sharing.shared_score(0, 1), 0);
+        assert_eq!(sharing.shared_score(3, 5), 19);
+        assert_eq!(parse_sharing("none").unwrap().name(), "none");
+        assert!(parse_sharing("0").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::burt::split_spec;
+
+/// scores a guess against the target, lower is better and 0 is perfect
+pub trait Fitness: Send + Sync {
+    /// wrapping fitnesses name the one they wrap as well, i.e. "samples:5:squared"
+    fn name(&self) -> String;
+
+    fn score(&self, guess: u32, target: u32, range: u32) -> u32;
+
+    /// how many guesses a burt makes per target, its guess and score are the averages of them
+    fn samples(&self) -> u32 {
+        1
+    }
+}
+
+fn distance_from(target: u32, guess: u32) -> u32 {
+    target.abs_diff(guess)
+}
+
+/// how far the guess is from the target, the original fitness
+pub struct AbsoluteError;
+
+impl Fitness for AbsoluteError {
+    fn name(&self) -> String {
+        String::from("absolute")
+    }
+
+    fn score(&self, guess: u32, target: u32, _range: u32) -> u32 {
+        distance_from(target, guess)
+    }
+}
+
+/// the distance squared, so guesses that are far off are punished a lot more
+pub struct SquaredError;
+
+impl Fitness for SquaredError {
+    fn name(&self) -> String {
+        String::from("squared")
+    }
+
+    fn score(&self, guess: u32, target: u32, _range: u32) -> u32 {
+        let distance = distance_from(target, guess);
+        distance.saturating_mul(distance)
+    }
+}
+
+/// the distance as a percentage of the target, so missing a small target is worse than missing a big one
+pub struct RelativeError;
+
+impl Fitness for RelativeError {
+    fn name(&self) -> String {
+        String::from("relative")
+    }
+
+    fn score(&self, guess: u32, target: u32, _range: u32) -> u32 {
+        // a target of 0 would divide by 0, so it counts as 1
+        (distance_from(target, guess) as u64 * 100 / target.max(1) as u64).min(u32::MAX as u64) as u32
+    }
+}
+
+/// the distance in bands of `width`, every guess within `width` of the target is perfect
+pub struct BandedError {
+    pub width: u32,
+}
+
+impl Fitness for BandedError {
+    fn name(&self) -> String {
+        format!("band:{}", self.width)
+    }
+
+    fn score(&self, guess: u32, target: u32, _range: u32) -> u32 {
+        distance_from(target, guess) / (self.width + 1)
+    }
+}
+
+/// averages another fitness over `samples` guesses, so a burt has to be consistent (have a low sigma) to do well
+pub struct MultiSample {
+    pub samples: u32,
+    pub inner: Box<dyn Fitness>,
+}
+
+impl Fitness for MultiSample {
+    fn name(&self) -> String {
+        format!("samples:{}:{}", self.samples, self.inner.name())
+    }
+
+    fn score(&self, guess: u32, target: u32, range: u32) -> u32 {
+        self.inner.score(guess, target, range)
+    }
+
+    fn samples(&self) -> u32 {
+        self.samples * self.inner.samples()
+    }
+}
+
+/// parses a fitness from "absolute", "squared", "relative", "band[:width]" or "samples:k[:fitness]"
+pub fn parse_fitness(spec: &str) -> Result<Box<dyn Fitness>, String> {
+    let spec = spec.trim().to_ascii_lowercase().replace(' ', ":");
+    let (name, arg) = split_spec(&spec);
+
+    match (name, arg) {
+        ("absolute", None) => Ok(Box::new(AbsoluteError)),
+        ("squared", None) => Ok(Box::new(SquaredError)),
+        ("relative", None) => Ok(Box::new(RelativeError)),
+        ("band", None) => Ok(Box::new(BandedError { width: 5 })),
+        ("band", Some(width)) => match width.parse::<u32>() {
+            Ok(width) => Ok(Box::new(BandedError { width })),
+            _ => Err(String::from("The band width must be a whole number!")),
+        },
+        ("samples", Some(arg)) => {
+            let (samples, inner) = match arg.split_once(':') {
+                Some((samples, inner)) => (samples, parse_fitness(inner)?),
+                None => (arg, Box::new(AbsoluteError) as Box<dyn Fitness>),
+            };
+            match samples.parse::<u32>() {
+                Ok(samples) if samples > 0 => Ok(Box::new(MultiSample { samples, inner })),
+                _ => Err(String::from("The number of samples must be at least 1!")),
+            }
+        }
+        _ => Err(format!("Unknown fitness: {} (expected absolute, squared, relative, band[:width] or samples:k[:fitness])", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+
+    #[test]
+    fn every_fitness_scores_and_trains() {
+        // guessing 3 when the target is 7
+        for (spec, score) in [("absolute", 4), ("squared", 16), ("relative", 57), ("band:3", 1), ("band:4", 0), ("samples:4:squared", 16)] {
+            let fitness = parse_fitness(spec).unwrap();
+            assert_eq!(fitness.score(3, 7, 100), score, "{}", spec);
+            assert_eq!(parse_fitness(&fitness.name()).unwrap().name(), fitness.name());
+
+            let mut burt_gang = seeded_gang(6);
+            burt_gang.fitness = fitness;
+            for _ in 0..burt_gang.generations {
+                burt_gang.train(false);
+            }
+            let (first, last) = (&burt_gang.history()[0], burt_gang.history().last().unwrap());
+            // whatever the fitness, the gang learns the target
+            assert!(last.mean_score < first.mean_score / 10.0, "{}: {} to {}", spec, first.mean_score, last.mean_score);
+            assert_eq!(last.min_score, 0, "{}", spec);
+        }
+        assert_eq!(parse_fitness("samples:2:samples:3").unwrap().samples(), 6);
+        assert_eq!(parse_fitness("Samples 2 band 1").unwrap().name(), "samples:2:band:1");
+        for bad in ["samples:0", "band:x", "cubed"] {
+            assert!(parse_fitness(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+}
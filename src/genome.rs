@@ -0,0 +1,294 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, LogNormal};
+use crate::crossover::Crossover;
+use crate::mutation::Mutation;
+use crate::sampling::{bounded_normal, Boundary};
+
+/// how a burt's genes turn into a guess, every burt of a gang has the same kind of genome.
+/// every gene is kept between 0 and the range, so any crossover and mutation works on any genome
+pub trait Genome: Send + Sync {
+    /// a saved gang needs this to know which genes its burts have, see `gene_names`
+    fn name(&self) -> String;
+
+    /// what each gene is called, in the order of the genes. these are also the keys of the genes in saved gangs
+    fn gene_names(&self) -> &'static [&'static str];
+
+    /// a guess for `input`, the genes are in the order of `gene_names`. a guess that could fall outside of
+    /// the range is kept in it with `boundary`
+    fn think(&self, genes: &[f32], input: f32, range: f32, boundary: Boundary, rng: &mut StdRng) -> f32;
+
+    /// the genes written out for people, i.e. "N(7.00, 0.50)"
+    fn describe(&self, genes: &[f32], range: f32) -> String;
+
+    /// the genes of a new burt, every gene is picked at random from the range
+    fn random_genes(&self, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        self.gene_names().iter().map(|_| rng.gen_range(0.0..range)).collect()
+    }
+
+    /// the genes of a child of two parents
+    fn cross(&self, mother: &[f32], father: &[f32], crossover: &dyn Crossover, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        clamped_cross(mother, father, crossover, range, rng)
+    }
+
+    /// mutates the genes of a new burt in place
+    fn mutate(&self, genes: &mut [f32], mutation: &dyn Mutation, mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        mutation.mutate(genes, mutation_rate, range, rng);
+    }
+}
+
+// crossover can go past the parents, so keep the genes in the range
+fn clamped_cross(mother: &[f32], father: &[f32], crossover: &dyn Crossover, range: f32, rng: &mut StdRng) -> Vec<f32> {
+    crossover.cross(mother, father, range, rng)
+        .into_iter()
+        .map(|gene| gene.clamp(0.0, range))
+        .collect()
+}
+
+/// a normal distribution, the original burt
+pub struct NormalGenome;
+
+impl Genome for NormalGenome {
+    fn name(&self) -> String {
+        String::from("normal")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["mu", "sigma"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, range: f32, boundary: Boundary, rng: &mut StdRng) -> f32 {
+        // a number from the normal distribution in the range, then * input
+        input * bounded_normal(genes[0], genes[1], 0.0, range, boundary, rng)
+    }
+
+    fn describe(&self, genes: &[f32], _range: f32) -> String {
+        format!("N({:.2}, {:.2})", genes[0], genes[1])
+    }
+}
+
+// the genes sorted from lowest to highest, for the genomes that are bounds
+fn sort_genes(genes: &mut [f32]) {
+    genes.sort_by(f32::total_cmp);
+}
+
+/// any number between two bounds with the same chance
+pub struct UniformGenome;
+
+impl Genome for UniformGenome {
+    fn name(&self) -> String {
+        String::from("uniform")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["low", "high"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, _range: f32, _boundary: Boundary, rng: &mut StdRng) -> f32 {
+        let number = if genes[0] < genes[1] { rng.gen_range(genes[0]..genes[1]) } else { genes[0] };
+        input * number
+    }
+
+    fn describe(&self, genes: &[f32], _range: f32) -> String {
+        format!("U({:.2}, {:.2})", genes[0], genes[1])
+    }
+
+    // the bounds are kept in order, so low is always the lowest
+    fn random_genes(&self, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        let mut genes: Vec<f32> = self.gene_names().iter().map(|_| rng.gen_range(0.0..range)).collect();
+        sort_genes(&mut genes);
+        genes
+    }
+
+    fn cross(&self, mother: &[f32], father: &[f32], crossover: &dyn Crossover, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        let mut genes = clamped_cross(mother, father, crossover, range, rng);
+        sort_genes(&mut genes);
+        genes
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation: &dyn Mutation, mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        mutation.mutate(genes, mutation_rate, range, rng);
+        sort_genes(genes);
+    }
+}
+
+/// a triangle between two bounds that peaks at the mode
+pub struct TriangularGenome;
+
+impl Genome for TriangularGenome {
+    fn name(&self) -> String {
+        String::from("triangular")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["low", "mode", "high"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, _range: f32, _boundary: Boundary, rng: &mut StdRng) -> f32 {
+        let (low, mode, high) = (genes[0], genes[1], genes[2]);
+        if high <= low {
+            return input * low;
+        }
+        // the inverse of the triangle's cumulative distribution
+        let u: f32 = rng.gen();
+        let number = if u < (mode - low) / (high - low) {
+            low + (u * (high - low) * (mode - low)).sqrt()
+        } else {
+            high - ((1.0 - u) * (high - low) * (high - mode)).sqrt()
+        };
+        input * number
+    }
+
+    fn describe(&self, genes: &[f32], _range: f32) -> String {
+        format!("Tri({:.2}, {:.2}, {:.2})", genes[0], genes[1], genes[2])
+    }
+
+    // low <= mode <= high is kept the same way as the uniform bounds
+    fn random_genes(&self, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        let mut genes: Vec<f32> = self.gene_names().iter().map(|_| rng.gen_range(0.0..range)).collect();
+        sort_genes(&mut genes);
+        genes
+    }
+
+    fn cross(&self, mother: &[f32], father: &[f32], crossover: &dyn Crossover, range: f32, rng: &mut StdRng) -> Vec<f32> {
+        let mut genes = clamped_cross(mother, father, crossover, range, rng);
+        sort_genes(&mut genes);
+        genes
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation: &dyn Mutation, mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        mutation.mutate(genes, mutation_rate, range, rng);
+        sort_genes(genes);
+    }
+}
+
+/// a log-normal distribution with the given mean and standard deviation, skewed towards the high guesses
+pub struct LogNormalGenome;
+
+impl Genome for LogNormalGenome {
+    fn name(&self) -> String {
+        String::from("lognormal")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["mean", "spread"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, range: f32, boundary: Boundary, rng: &mut StdRng) -> f32 {
+        // the genes are the mean and standard deviation of the guesses, not of their logarithm
+        let mean = genes[0].max(f32::EPSILON);
+        let log_variance = (1.0 + (genes[1] * genes[1]) / (mean * mean)).ln();
+        let number = match LogNormal::new(mean.ln() - log_variance / 2.0, log_variance.sqrt()) {
+            Ok(log_normal) => {
+                let first = log_normal.sample(rng);
+                boundary.apply(first, 0.0, range, || log_normal.sample(rng))
+            }
+            Err(_) => mean.min(range),
+        };
+        input * number
+    }
+
+    fn describe(&self, genes: &[f32], _range: f32) -> String {
+        format!("LogN(mean {:.2}, spread {:.2})", genes[0], genes[1])
+    }
+}
+
+/// two normal distributions, the first is picked `weight / range` of the time
+pub struct MixtureGenome;
+
+impl Genome for MixtureGenome {
+    fn name(&self) -> String {
+        String::from("mixture")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["mu1", "sigma1", "mu2", "sigma2", "weight"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, range: f32, boundary: Boundary, rng: &mut StdRng) -> f32 {
+        // the weight is a gene like any other, so it lives in the range as well
+        let first = rng.gen_bool((genes[4] / range).clamp(0.0, 1.0) as f64);
+        let (mu, sigma) = if first { (genes[0], genes[1]) } else { (genes[2], genes[3]) };
+        input * bounded_normal(mu, sigma, 0.0, range, boundary, rng)
+    }
+
+    fn describe(&self, genes: &[f32], range: f32) -> String {
+        let weight = (genes[4] / range).clamp(0.0, 1.0) * 100.0;
+        format!("{:.0}% N({:.2}, {:.2}) + {:.0}% N({:.2}, {:.2})", weight, genes[0], genes[1], 100.0 - weight, genes[2], genes[3])
+    }
+}
+
+/// a tiny linear model, `slope * input + intercept` plus normal noise
+pub struct LinearGenome;
+
+impl Genome for LinearGenome {
+    fn name(&self) -> String {
+        String::from("linear")
+    }
+
+    fn gene_names(&self) -> &'static [&'static str] {
+        &["slope", "intercept", "noise"]
+    }
+
+    fn think(&self, genes: &[f32], input: f32, range: f32, boundary: Boundary, rng: &mut StdRng) -> f32 {
+        // the noise is centred on the line, so the line is what gets kept in the range
+        bounded_normal(genes[0] * input + genes[1], genes[2], 0.0, range, boundary, rng)
+    }
+
+    fn describe(&self, genes: &[f32], _range: f32) -> String {
+        format!("{:.2}x + {:.2} ± {:.2}", genes[0], genes[1], genes[2])
+    }
+}
+
+/// parses a genome from "normal", "uniform", "triangular", "lognormal", "mixture" or "linear"
+pub fn parse_genome(spec: &str) -> Result<Box<dyn Genome>, String> {
+    match spec.trim().to_ascii_lowercase().as_str() {
+        "normal" => Ok(Box::new(NormalGenome)),
+        "uniform" => Ok(Box::new(UniformGenome)),
+        "triangular" => Ok(Box::new(TriangularGenome)),
+        "lognormal" => Ok(Box::new(LogNormalGenome)),
+        "mixture" => Ok(Box::new(MixtureGenome)),
+        "linear" => Ok(Box::new(LinearGenome)),
+        spec => Err(format!("Unknown genome: {} (expected normal, uniform, triangular, lognormal, mixture or linear)", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::BurtGang;
+    use crate::burt::tests::seeded_gang;
+
+    #[test]
+    fn every_genome_guesses_and_trains() {
+        let boundaries = [Boundary::Clamp, Boundary::Reflect, Boundary::Truncate];
+        for (x, spec) in ["normal", "uniform", "triangular", "lognormal", "mixture", "linear"].into_iter().enumerate() {
+            let mut burt_gang = seeded_gang(13);
+            burt_gang.set_genome(parse_genome(spec).unwrap());
+            burt_gang.boundary = boundaries[x % boundaries.len()];
+            assert_eq!(burt_gang.genome.name(), spec);
+            assert_eq!(burt_gang.burts.len(), 60);
+            for _ in 0..5 {
+                burt_gang.train(false);
+                burt_gang.train(true);
+            }
+
+            let gene_count = burt_gang.genome.gene_names().len();
+            for b in burt_gang.iter() {
+                assert_eq!(b.genes().len(), gene_count);
+                assert!(b.genes().iter().all(|gene| (0.0..=100.0).contains(gene)), "{}: {:?}", spec, b.genes());
+                assert!(b.get_guess().unwrap() <= 100);
+            }
+            assert_eq!(burt_gang.history().last().unwrap().gene_stddevs.len(), gene_count);
+
+            // the genes are saved under the genome's names for them
+            let path = std::env::temp_dir().join(format!("malb_genome_{}_{}.txt", spec, std::process::id()));
+            burt_gang.save(&path).unwrap();
+            let loaded = BurtGang::load(&path).unwrap();
+            std::fs::remove_file(&path).unwrap();
+            assert_eq!(loaded.genome.name(), spec);
+            assert_eq!(loaded.burts, burt_gang.burts);
+        }
+        assert!(parse_genome("cauchy").is_err());
+    }
+}
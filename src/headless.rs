@@ -0,0 +1,102 @@
+use std::io::{ErrorKind, stdout, Write};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use crate::burt::BurtGang;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(String::from("expected csv or json")),
+        }
+    }
+}
+
+// a missing value is left empty in csv
+fn csv_value(value: Option<u32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+// and null in json
+fn json_value(value: Option<u32>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_else(|| "null".to_string())
+}
+
+// writes a line of stats, false once nothing reads them any more (i.e. piped into head)
+fn write_line(out: &mut impl Write, line: &str) -> bool {
+    match writeln!(out, "{}", line) {
+        Ok(()) => true,
+        Err(e) if e.kind() == ErrorKind::BrokenPipe => false,
+        Err(e) => panic!("Failed to write to stdout: {}", e),
+    }
+}
+
+/// trains the gang for all of its generations, printing the stats of every generation to stdout.
+/// returns how long training took and how it ended, for the report
+pub fn run_headless(burt_gang: &mut BurtGang, format: OutputFormat, advanced: bool) -> (Duration, String) {
+    let stdout = stdout();
+    let mut out = stdout.lock();
+
+    let start = Instant::now();
+    // an empty gang doesn't train, so the generations would never go by
+    if burt_gang.burts.is_empty() {
+        let stopped = String::from("Stopped: there are no burts to train");
+        eprintln!("{}", stopped);
+        return (start.elapsed(), stopped);
+    }
+    let closed = || String::from("Stopped: nothing is reading the stats any more");
+
+    if format == OutputFormat::Csv
+        && !write_line(&mut out, "generation,average_guess,average_score,best_score,perfect_count,unique_genomes,rms_distance") {
+        return (start.elapsed(), closed());
+    }
+
+    while burt_gang.current_generation < burt_gang.generations {
+        burt_gang.train(advanced);
+        if let Some(Err(e)) = burt_gang.checkpoint() {
+            eprintln!("Failed to save checkpoint to {}: {}", burt_gang.checkpoint_path.display(), e);
+        }
+
+        let (unique_genomes, rms_distance) = burt_gang.history().last()
+            .map_or((0, 0.0), |stats| (stats.unique_genomes, stats.rms_distance));
+        let line = match format {
+            OutputFormat::Csv => format!("{},{},{},{},{},{},{:.3}",
+                                         burt_gang.current_generation,
+                                         csv_value(burt_gang.average_guess()),
+                                         csv_value(burt_gang.average_score()),
+                                         csv_value(burt_gang.best_score()),
+                                         burt_gang.perfect_count(),
+                                         unique_genomes,
+                                         rms_distance),
+            OutputFormat::Json => format!("{{\"generation\":{},\"average_guess\":{},\"average_score\":{},\"best_score\":{},\"perfect_count\":{},\"unique_genomes\":{},\"rms_distance\":{:.3}}}",
+                                          burt_gang.current_generation,
+                                          json_value(burt_gang.average_guess()),
+                                          json_value(burt_gang.average_score()),
+                                          json_value(burt_gang.best_score()),
+                                          burt_gang.perfect_count(),
+                                          unique_genomes,
+                                          rms_distance),
+        };
+        if !write_line(&mut out, &line) {
+            return (start.elapsed(), closed());
+        }
+
+        // stdout is only for the stats, so the reason goes to stderr
+        if let Some(reason) = burt_gang.stop_reason(start.elapsed()) {
+            let stopped = format!("Stopped at generation {}/{}: {}", burt_gang.current_generation, burt_gang.generations, reason);
+            eprintln!("{}", stopped);
+            return (start.elapsed(), stopped);
+        }
+    }
+    (start.elapsed(), format!("Training complete: trained all {} generations", burt_gang.generations))
+}
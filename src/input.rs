@@ -4,14 +4,12 @@ use better_term::{Color, flush_styles};
 
 fn read_input<S: Into<String>>(prompt: S) -> String {
     print!("{}", prompt.into());
-    let r = stdout().flush();
-    if r.is_err() {
-        panic!("Error flusing output: {}", r.unwrap_err());
+    if let Err(e) = stdout().flush() {
+        panic!("Error flusing output: {}", e);
     }
     let mut buffer = String::new();
-    let r2 = stdin().read_line(&mut buffer);
-    if r2.is_err() {
-        panic!("Error in reading input: {}", r.unwrap_err());
+    if let Err(e) = stdin().read_line(&mut buffer) {
+        panic!("Error in reading input: {}", e);
     }
     buffer.replace("\n", "").replace("\r", "")
 }
@@ -0,0 +1,237 @@
+use std::ops::Range;
+use crate::burt::{check_rate, parse_selection, SelectionStrategy};
+
+/// a sub-population of the gang, its burts are only ranked against and bred with each other.
+/// anything the island doesn't set comes from the gang
+#[derive(Default)]
+pub struct Island {
+    pub survival_rate: Option<f32>,
+    pub mutation_rate: Option<f32>,
+    pub selection: Option<Box<dyn SelectionStrategy>>,
+}
+
+impl Island {
+    /// sets one of the island's own settings, the names are the same as for the `change` command
+    pub fn set(&mut self, var: &str, value: &str) -> Result<(), String> {
+        match var {
+            "survival_rate" | "mutation_rate" => {
+                let rate = value.parse::<f32>().map_err(|_| format!("Invalid value: {} expects a value between 0 and 1!", var))?;
+                check_rate(&var.replace('_', " "), rate)?;
+                if var == "survival_rate" {
+                    self.survival_rate = Some(rate);
+                } else {
+                    self.mutation_rate = Some(rate);
+                }
+            }
+            "selection" => self.selection = Some(parse_selection(value)?),
+            _ => return Err(format!("Islands can't change {} (expected survival_rate, mutation_rate or selection)", var)),
+        }
+        Ok(())
+    }
+
+    /// the settings the island has of its own as (name, value), the way `set` takes them
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let mut settings = Vec::new();
+        if let Some(rate) = self.survival_rate {
+            settings.push(("survival_rate", rate.to_string()));
+        }
+        if let Some(rate) = self.mutation_rate {
+            settings.push(("mutation_rate", rate.to_string()));
+        }
+        if let Some(selection) = &self.selection {
+            settings.push(("selection", selection.name()));
+        }
+        settings
+    }
+}
+
+/// parses "2:mutation_rate:0.1" into the island and the setting, for the command line and saved gangs
+pub fn parse_island_setting(spec: &str) -> Result<(usize, String, String), String> {
+    let mut parts = spec.trim().splitn(3, ':');
+    match (parts.next().map(|i| i.parse::<usize>()), parts.next(), parts.next()) {
+        (Some(Ok(island)), Some(var), Some(value)) => {
+            // checked on a throwaway island, the real one may not exist yet
+            Island::default().set(var, value)?;
+            Ok((island, var.to_string(), value.to_string()))
+        }
+        _ => Err(format!("Invalid island setting: {} (expected island:variable:value, i.e. 1:mutation_rate:0.1)", spec)),
+    }
+}
+
+/// which islands the migrants of an island go to
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Topology {
+    /// the next island, the last one's go to the first
+    Ring,
+    /// a random other island every time
+    Random,
+}
+
+/// every `every` generations the best `migrants` burts of every island are copied over the worst of another island
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Migration {
+    pub topology: Topology,
+    // 0 turns migration off
+    pub every: u32,
+    pub migrants: u32,
+}
+
+impl Default for Migration {
+    fn default() -> Self {
+        Self { topology: Topology::Ring, every: 10, migrants: 1 }
+    }
+}
+
+impl Migration {
+    /// "none" when migrants never move
+    pub fn name(&self) -> String {
+        if self.every == 0 {
+            return String::from("none");
+        }
+        let topology = match self.topology {
+            Topology::Ring => "ring",
+            Topology::Random => "random",
+        };
+        format!("{}:{}:{}", topology, self.every, self.migrants)
+    }
+}
+
+/// parses a migration from "none", "ring[:every[:migrants]]" or "random[:every[:migrants]]"
+pub fn parse_migration(spec: &str) -> Result<Migration, String> {
+    let spec = spec.trim().to_ascii_lowercase().replace(' ', ":");
+    let mut parts = spec.split(':');
+    let topology = match parts.next().unwrap_or_default() {
+        "none" if parts.next().is_none() => return Ok(Migration { every: 0, ..Migration::default() }),
+        "ring" => Topology::Ring,
+        "random" => Topology::Random,
+        _ => return Err(format!("Unknown migration: {} (expected none, ring[:every[:migrants]] or random[:every[:migrants]])", spec)),
+    };
+    let mut migration = Migration { topology, ..Migration::default() };
+    if let Some(every) = parts.next() {
+        migration.every = match every.parse::<u32>() {
+            Ok(every) if every > 0 => every,
+            _ => return Err(String::from("Migrants have to move every 1 or more generations!")),
+        };
+    }
+    if let Some(migrants) = parts.next() {
+        migration.migrants = migrants.parse::<u32>().map_err(|_| String::from("The number of migrants must be a whole number!"))?;
+    }
+    if parts.next().is_some() {
+        return Err(format!("Invalid migration: {}", spec));
+    }
+    Ok(migration)
+}
+
+/// the burts of island `island` out of `islands`, the burts are split into runs of (nearly) the same size in id order
+pub fn island_range(island: usize, islands: usize, burt_count: usize) -> Range<usize> {
+    let start = |island: usize| (island as u64 * burt_count as u64 / islands as u64) as usize;
+    start(island)..start(island + 1)
+}
+
+/// the island burt `x` is on, the inverse of `island_range`
+pub fn island_of(x: usize, islands: usize, burt_count: usize) -> usize {
+    (((x as u64 + 1) * islands as u64 - 1) / burt_count as u64) as usize
+}
+
+/// how an island did in the last generation
+#[derive(Clone, Debug, PartialEq)]
+pub struct IslandStats {
+    pub burts: usize,
+    pub average_guess: Option<u32>,
+    pub average_score: Option<u32>,
+    pub best_score: Option<u32>,
+    pub perfect_count: u32,
+    // the spread of the first gene, a low one means the island has settled on an answer
+    pub gene_stddev: f32,
+}
+
+/// an island's settings with the gang's filled in, for showing them
+#[derive(Clone, Debug, PartialEq)]
+pub struct IslandSettings {
+    pub burts: usize,
+    pub survival_rate: f32,
+    pub mutation_rate: f32,
+    pub selection: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+    use crate::mutation::parse_mutation;
+
+    #[test]
+    fn islands_breed_on_their_own() {
+        for advanced in [false, true] {
+            let mut burt_gang = seeded_gang(13);
+            burt_gang.set_islands(3);
+            burt_gang.migration = parse_migration("none").unwrap();
+            burt_gang.mutation_rate = 0.0;
+            let before = burt_gang.burts.clone();
+            for _ in 0..5 {
+                burt_gang.train(advanced);
+            }
+
+            // without mutation or migration every island is left with genes it started with
+            assert_eq!(burt_gang.island_stats().len(), 3);
+            for island in 0..3 {
+                let range = island_range(island, 3, 60);
+                assert_eq!(burt_gang.island_stats()[island].burts, range.len());
+                for b in &burt_gang.burts[range.clone()] {
+                    assert!(before[range.clone()].iter().any(|old| old.genes() == b.genes()), "island {} got a stranger", island);
+                }
+            }
+        }
+
+        // more islands than burts leaves some of them empty
+        let mut burt_gang = seeded_gang(13);
+        burt_gang.populate(2, false);
+        burt_gang.set_islands(5);
+        burt_gang.train(false);
+        burt_gang.train(true);
+    }
+
+    #[test]
+    fn the_best_burts_migrate() {
+        for advanced in [false, true] {
+            let mut burt_gang = seeded_gang(17);
+            burt_gang.set_islands(3);
+            burt_gang.migration = parse_migration("ring:1:2").unwrap();
+            // mutating every gene of every child, so no child has the genes of the burt it was bred from
+            burt_gang.mutation_rate = 0.5;
+            burt_gang.mutation = parse_mutation("gene:1").unwrap();
+            let before = burt_gang.burts.clone();
+            burt_gang.train(advanced);
+
+            // in sticky training the best burts are replaced too, the genes they had before are the ones that migrate
+            for from in 0..3 {
+                let best_score = burt_gang.island_stats()[from].best_score.unwrap().to_string();
+                let best: Vec<usize> = island_range(from, 3, 60).filter(|x| burt_gang.burts[*x].get_score_display() == best_score).collect();
+                let next = &burt_gang.burts[island_range((from + 1) % 3, 3, 60)];
+                assert!(best.iter().any(|x| next.iter().any(|b| b.genes() == before[*x].genes())),
+                    "the best burt of island {} didn't migrate (advanced: {})", from, advanced);
+            }
+        }
+
+        let mut burt_gang = seeded_gang(17);
+        burt_gang.set_islands(3);
+        assert!(parse_migration("ring:0").is_err());
+        assert_eq!(parse_migration("Random 5").unwrap().name(), "random:5:1");
+        assert!(burt_gang.set_island(3, "mutation_rate", "0.1").is_err());
+        assert!(burt_gang.set_island(0, "elite_count", "1").is_err());
+    }
+
+    #[test]
+    fn island_ranges_cover_every_burt() {
+        for (islands, burt_count) in [(1, 10), (3, 60), (4, 10), (5, 2)] {
+            let mut next = 0;
+            for island in 0..islands {
+                let range = island_range(island, islands, burt_count);
+                assert_eq!(range.start, next);
+                assert!(range.clone().all(|x| island_of(x, islands, burt_count) == island));
+                next = range.end;
+            }
+            assert_eq!(next, burt_count);
+        }
+    }
+}
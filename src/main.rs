@@ -1,14 +1,16 @@
-#![feature(int_abs_diff)]
+#![cfg_attr(test, feature(test))]
 
-use std::{env, thread};
+use std::{env, fs, thread};
 use std::io::stdout;
+use std::process::exit;
 use std::sync::mpsc;
 use std::time::{Duration, Instant};
 use crossterm::{event, execute, terminal};
 use crossterm::cursor::MoveTo;
 use crossterm::terminal::{ClearType, disable_raw_mode, enable_raw_mode};
 use crossterm::event::{Event as CEvent, KeyCode};
-use log::{info, LevelFilter};
+use log::{error, info, LevelFilter};
+use rand::{Rng, thread_rng};
 use tui::backend::CrosstermBackend;
 use tui::layout::{Alignment, Constraint, Direction, Layout};
 use tui::style::{Color, Modifier, Style};
@@ -16,46 +18,134 @@ use tui::Terminal;
 use tui::text::{Span, Spans};
 use tui::widgets::{Block, Borders, BorderType, ListState, Paragraph, Tabs};
 use tlogger::{init_logger, set_default_level, TuiLoggerLevelOutput, TuiLoggerSmartWidget};
-use crate::burt::{BurtGang, get_burt_gang, populate_burts};
-use crate::ui::{draw_burts, draw_home, Event, MenuItem};
+use crate::args::{Args, HELP};
+use crate::burt::{BurtGang, check_burt_count, check_crossover_rate, check_islands, check_range, check_rate, check_samples_per_eval, check_target, get_burt_gang, parse_selection};
+use crate::crossover::parse_crossover;
+use crate::fitness::parse_fitness;
+use crate::genome::parse_genome;
+use crate::diversity::parse_sharing;
+use crate::island::parse_migration;
+use crate::stopping::{parse_stop_conditions, stop_conditions_name};
+use crate::sampling::Boundary;
+use crate::mutation::parse_mutation;
+use crate::schedule::parse_schedule;
+use crate::headless::run_headless;
+use crate::report::run_report;
+use crate::trainer::{Command, DEFAULT_CONVERGE_PATIENCE, Trainer};
+use crate::ui::{draw_burts, draw_histogram, draw_home, draw_islands, draw_stats, Event, MenuItem};
 
 pub(crate) mod input;
 mod ui;
 mod burt;
+mod args;
+mod headless;
+mod crossover;
+mod mutation;
+mod trainer;
+mod schedule;
+mod fitness;
+mod genome;
+mod sampling;
+mod island;
+mod diversity;
+mod stopping;
+mod report;
 
 pub const TRAIN_STICKY: bool = false;
 
+// the delays the '+' and '-' keys step between, below the minimum there is no delay
+const MIN_DELAY: Duration = Duration::from_millis(1);
+const MAX_DELAY: Duration = Duration::from_secs(2);
+
+// load the gang to resume, or make a new one from the arguments (asking for them if there are none)
+fn setup_burt_gang(args: &Args, seed: u64, display: bool) -> BurtGang {
+    let mut burt_gang = if let Some(path) = &args.resume {
+        match BurtGang::load(path) {
+            Ok(burt_gang) => burt_gang,
+            Err(e) => {
+                eprintln!("Failed to resume from {}: {}", path.display(), e);
+                exit(1);
+            }
+        }
+    } else if args.non_interactive() {
+        args.burt_gang(seed, display)
+    } else {
+        get_burt_gang(seed)
+    };
+    if let Err(e) = args.configure(&mut burt_gang) {
+        eprintln!("Invalid input: {}", e);
+        exit(1);
+    }
+    burt_gang
+}
+
 fn main() {
     // get arguments
     let args: Vec<String> = env::args().collect();
+    let args = match Args::parse(&args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("Invalid input: {}", e);
+            eprintln!("Run with --help to see the available options.");
+            exit(1);
+        }
+    };
+
+    if args.help {
+        print!("{}", HELP);
+        return;
+    }
+
+    // the training threads, rayon uses every core by default
+    if let Some(threads) = args.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build_global()
+            .expect("Failed to start the training threads");
+    }
+
+    // get the seed for the run, or pick a random one so the run can still be replayed later
+    let seed = args.seed.unwrap_or_else(|| thread_rng().gen());
+
+    // headless mode trains straight through without ever touching the terminal
+    if args.headless {
+        let mut burt_gang = setup_burt_gang(&args, seed, false);
+        // a resumed gang is trained the way it was saved, unless --strategy says otherwise
+        let sticky = burt_gang.sticky;
+        let (trained_for, stopped) = run_headless(&mut burt_gang, args.format, sticky);
+        // stdout is for the stats, so the report is only written to the file
+        if let Some(path) = &args.report {
+            if let Err(e) = fs::write(path, run_report(&burt_gang, trained_for, None, Some(&stopped))) {
+                eprintln!("Failed to write the report to {}: {}", path.display(), e);
+            }
+        }
+        return;
+    }
 
     // clear the screen and set terminal position
     execute!(stdout(), terminal::Clear(ClearType::All), MoveTo(0,0)).expect("Failed to clear screen! Is this terminal supported?");
 
     // initialize the burts
-    let mut burt_gang = if args.contains(&"-d".to_string()) || args.contains(&"--default".to_string()) {
-        let default_range = 100;
-        BurtGang::new(populate_burts(150,
-                                     default_range.clone(), true),
-                      default_range, 7, 150,
-                      0.25, 0.01)
-    } else {
-        get_burt_gang()
-    };
+    let burt_gang = setup_burt_gang(&args, seed, true);
 
+    // a resumed gang brings its own seed
+    let seed = burt_gang.seed;
+    let sticky = burt_gang.sticky;
     let starting_burt_count = burt_gang.burts.len() as u32;
-    let starting_range = burt_gang.range;
-    let starting_target = burt_gang.target;
-    let starting_survival_rate = burt_gang.survival_rate;
-    let starting_mutation_rate = burt_gang.mutation_rate;
-    let starting_generations = burt_gang.generations;
+    // every setting the run started with, from the arguments, the prompts or the resumed file
+    let starting_gang = burt_gang.settings_copy();
 
     //println!("{}", &burt_gang);
 
+    // the gang is trained on its own thread from here on, the UI only sees snapshots of it
+    let mut trainer = Trainer::spawn(burt_gang, sticky);
+    let session_start = Instant::now();
+
     // initialize logger
     init_logger(LevelFilter::Trace).unwrap();
     set_default_level(LevelFilter::Trace);
     info!(target:"MaLB", "Starting renderer");
+    info!(target:"MaLB", "Using seed {}", seed);
 
     // enable terminal raw mode and set up the terminal
     enable_raw_mode().expect("Failed to enable raw mode; is this terminal supported?");
@@ -91,7 +181,7 @@ fn main() {
     });
 
     // render loop variables
-    let menu_titles = vec!["Home", "Burts", "Log", "Quit"];
+    let menu_titles = ["Home", "Burts", "Graphs", "Distribution", "Islands", "Log", "Quit"];
     let mut active_menu_item = MenuItem::Home;
 
     let mut burt_list_state = ListState::default();
@@ -100,22 +190,39 @@ fn main() {
     let mut input_mode = false;
     let mut user_input = String::new();
     let mut input_ready = false;
-    let mut input_mode_prompt = format!("Input");
+    let mut input_mode_prompt = String::from("Input");
 
     let default_footer_txt = format!("MaLB v{} 2022 created and maintained by Eric Shreve and Ben Snedeker", env!("CARGO_PKG_VERSION"));
     let mut footer_txt = default_footer_txt.clone();
     let default_footer_col = Color::LightCyan;
-    let mut footer_col = default_footer_col.clone();
+    let mut footer_col = default_footer_col;
 
     let error_time = Duration::from_millis(3000);
     let mut error_start: Option<Instant> = None;
 
-    let generation_delay = Duration::from_millis(0);
-    let mut last_gen_run: Option<Instant> = None;
-    let mut running = false;
-
     // start the main loop
     loop {
+        // take in what the training thread sent since the last frame
+        for reply in trainer.update() {
+            match reply {
+                Ok(msg) if msg.is_empty() => continue,
+                Ok(msg) => {
+                    footer_txt = msg;
+                    footer_col = Color::LightYellow;
+                }
+                Err(e) => {
+                    footer_txt = e;
+                    footer_col = Color::LightRed;
+                }
+            }
+            error_start = Some(Instant::now());
+        }
+        let burt_gang = trainer.snapshot();
+        // the gang can shrink when it's changed or loaded
+        if burt_list_state.selected().is_none_or(|selected| selected >= burt_gang.len()) {
+            burt_list_state.select(Some(0));
+        }
+
         // draw the UI
         let footer = Paragraph::new(footer_txt.clone())
             .style(Style::default().fg(footer_col))
@@ -127,7 +234,7 @@ fn main() {
                     .title("Info")
                     .border_type(BorderType::Plain)
             );
-        terminal.draw(|mut rect| {
+        terminal.draw(|rect| {
             // setup the layout
             let size = rect.size();
             let chunks = Layout::default()
@@ -168,20 +275,20 @@ fn main() {
             // handle the main page
             match active_menu_item {
                 MenuItem::Home => {
-                    draw_home(&mut rect, &chunks, &mut burt_gang);
+                    draw_home(rect, &chunks, burt_gang);
                 }
                 MenuItem::Burts => {
-                    draw_burts(&mut rect, &chunks, &burt_gang, &mut burt_list_state);
+                    draw_burts(rect, &chunks, burt_gang, &mut burt_list_state);
 
                     if input_mode {
-                        input_mode_prompt = format!("Enter a Burt ID");
+                        input_mode_prompt = String::from("Enter a Burt ID");
                     }
 
                     if input_ready {
                         // handle burt id search
                         for c in user_input.chars() {
                             if !c.is_numeric() {
-                                footer_txt = format!("Invalid Input: must be a number!");
+                                footer_txt = String::from("Invalid Input: must be a number!");
                                 footer_col = Color::LightRed;
                                 user_input = String::new();
                                 error_start = Some(Instant::now());
@@ -192,7 +299,7 @@ fn main() {
                         let number = user_input.parse::<usize>();
 
                         if number.is_err() {
-                            footer_txt = format!("Invalid Input: must be a number!");
+                            footer_txt = String::from("Invalid Input: must be a number!");
                             footer_col = Color::LightRed;
                             input_ready = false;
                             user_input = String::new();
@@ -217,6 +324,15 @@ fn main() {
                         user_input = String::new();
                     }
                 }
+                MenuItem::Stats => {
+                    draw_stats(rect, &chunks, burt_gang);
+                }
+                MenuItem::Histogram => {
+                    draw_histogram(rect, &chunks, burt_gang);
+                }
+                MenuItem::Islands => {
+                    draw_islands(rect, &chunks, burt_gang);
+                }
                 MenuItem::Log => {
                     let tui_sm = TuiLoggerSmartWidget::default()
                         .style_error(Style::default().fg(Color::Red))
@@ -253,20 +369,18 @@ fn main() {
             if !input_mode {
                 rect.render_widget(footer, chunks[2]);
             }
-            input_mode_prompt = format!("Input");
+            input_mode_prompt = String::from("Input");
         }).expect("Failed to draw frame with TUI");
 
         // handle keypresses for the UI
         let event_poll = rx.recv_timeout(Duration::from_millis(200));
-        if event_poll.is_ok() {
-            match event_poll.unwrap() {
+        if let Ok(event) = event_poll {
+            match event {
                 Event::Input(event) => {
-                    if event.code == KeyCode::Char('c') {
-                        if event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) {
-                            // add ctrl+c functionality
-                            // if the program is processing for a long time this wont complete until it's done processing
-                            break;
-                        }
+                    if event.code == KeyCode::Char('c') && event.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) {
+                        // add ctrl+c functionality
+                        // the training thread finishes the generation it's on before the program quits
+                        break;
                     }
                     if input_mode {
                         match event.code {
@@ -281,23 +395,23 @@ fn main() {
                                 input_mode = !input_mode;
                             }
                             KeyCode::Backspace => {
-                                if user_input.len() > 0 {
-                                    user_input.remove(user_input.len() - 1);
-                                }
+                                user_input.pop();
                             }
                             _ => {}
                         }
                     } else {
                         match event.code {
                             KeyCode::Char('q') => break,
-                            KeyCode::Char('s') => running = !running,
+                            KeyCode::Char('s') => {
+                                trainer.send(if burt_gang.running { Command::Pause } else { Command::Resume });
+                            }
                             KeyCode::Char('r') => {
-                                burt_gang = BurtGang::new(populate_burts(starting_burt_count.clone(),
-                                                                         starting_range.clone(), false),
-                                                          starting_range.clone(),
-                                                          starting_target.clone(), starting_generations.clone(),
-                                                          starting_survival_rate.clone(),
-                                                          starting_mutation_rate.clone())
+                                let mut fresh = starting_gang.settings_copy();
+                                trainer.change(move |burt_gang| {
+                                    fresh.populate(starting_burt_count, false);
+                                    *burt_gang = fresh;
+                                    Ok(String::new())
+                                });
                             }
                             KeyCode::Char('h') => {
                                 active_menu_item = MenuItem::Home;
@@ -305,20 +419,34 @@ fn main() {
                             KeyCode::Char('b') => {
                                 active_menu_item = MenuItem::Burts;
                             },
+                            KeyCode::Char('g') => {
+                                active_menu_item = MenuItem::Stats;
+                            },
+                            KeyCode::Char('d') => {
+                                active_menu_item = MenuItem::Histogram;
+                            },
+                            KeyCode::Char('i') => {
+                                active_menu_item = MenuItem::Islands;
+                            },
                             KeyCode::Char('t') => {
                                 input_mode = !input_mode;
                             }
                             KeyCode::Char('l') => {
                                 active_menu_item = MenuItem::Log;
                             },
-                            KeyCode::Char('e') => {
-                                info!(target:"MalB", "User forced run of training generation: {}/{}",
-                                    burt_gang.current_generation, burt_gang.generations);
-                                burt_gang.train(TRAIN_STICKY);
-                            },
+                            KeyCode::Char('e') => trainer.send(Command::Step(1)),
+                            // halve or double the delay between generations, down to no delay at all
+                            KeyCode::Char('+') => {
+                                let delay = if burt_gang.delay <= MIN_DELAY { Duration::ZERO } else { burt_gang.delay / 2 };
+                                trainer.send(Command::Delay(delay));
+                            }
+                            KeyCode::Char('-') => {
+                                let delay = (burt_gang.delay * 2).clamp(MIN_DELAY, MAX_DELAY);
+                                trainer.send(Command::Delay(delay));
+                            }
                             KeyCode::Down => {
                                 if let Some(selected) = burt_list_state.selected() {
-                                    if selected >= burt_gang.len() - 1 {
+                                    if selected >= burt_gang.len().saturating_sub(1) {
                                         burt_list_state.select(Some(0));
                                     } else {
                                         burt_list_state.select(Some(selected + 1));
@@ -343,23 +471,8 @@ fn main() {
             }
         }
 
-        // handle running training
-        if running && burt_gang.current_generation < burt_gang.generations {
-            if last_gen_run.is_some() {
-                if last_gen_run.unwrap().elapsed() >= generation_delay {
-                    burt_gang.train(TRAIN_STICKY);
-                    last_gen_run = Some(Instant::now());
-                }
-            } else {
-                burt_gang.train(TRAIN_STICKY);
-                last_gen_run = Some(Instant::now());
-            }
-        } else {
-            // training is complete, do something here
-        }
-
         // handle input
-        let burt_list_mode = if let MenuItem::Burts = active_menu_item { true } else { false };
+        let burt_list_mode = matches!(active_menu_item, MenuItem::Burts);
         if input_ready && !burt_list_mode {
             if !user_input.is_empty() {
                 let mut cmd_args = user_input.split(" ").collect::<Vec<&str>>();
@@ -368,7 +481,7 @@ fn main() {
                 match cmd.to_ascii_lowercase().as_str() {
                     "change" => {
                         if cmd_args.len() < 2 {
-                            footer_txt = format!("change takes a variable name and a new value!");
+                            footer_txt = String::from("change takes a variable name and a new value!");
                             footer_col = Color::LightRed;
                             error_start = Some(Instant::now());
                             input_ready = false;
@@ -376,27 +489,32 @@ fn main() {
                             continue;
                         }
 
-                        let var = cmd_args.get(0).unwrap();
+                        let var = cmd_args.first().unwrap();
                         let value = cmd_args.get(1).unwrap();
 
                         match *var {
                             "range" => {
-                                let parsed = value.parse::<u32>();
-                                if parsed.is_err() {
-                                    footer_txt = format!("Invalid value: range expects a value above 0!");
-                                    footer_col = Color::LightRed;
-                                    error_start = Some(Instant::now());
-                                    input_ready = false;
-                                    user_input = String::new();
-                                    continue;
-                                }
-                                let n = parsed.unwrap();
-                                burt_gang.range = n;
+                                let n = match value.parse::<u32>() {
+                                    Ok(n) if check_range(n).is_ok() => n,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: range expects a value above 0!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    check_target(burt_gang.target, n)?;
+                                    burt_gang.range = n;
+                                    Ok(String::new())
+                                });
                             }
                             "target" => {
                                 let parsed = value.parse::<u32>();
                                 if parsed.is_err() {
-                                    footer_txt = format!("Invalid value: target expects a value above 0!");
+                                    footer_txt = String::from("Invalid value: target expects a value above 0!");
                                     footer_col = Color::LightRed;
                                     error_start = Some(Instant::now());
                                     input_ready = false;
@@ -404,12 +522,17 @@ fn main() {
                                     continue;
                                 }
                                 let n = parsed.unwrap();
-                                burt_gang.target = n;
+                                // the range is the training thread's, so the target is checked there
+                                trainer.change(move |burt_gang| {
+                                    check_target(n, burt_gang.range)?;
+                                    burt_gang.target = n;
+                                    Ok(String::new())
+                                });
                             }
                             "generations" => {
                                 let parsed = value.parse::<u32>();
                                 if parsed.is_err() {
-                                    footer_txt = format!("Invalid value: generations expects a value above 0!");
+                                    footer_txt = String::from("Invalid value: generations expects a value above 0!");
                                     footer_col = Color::LightRed;
                                     error_start = Some(Instant::now());
                                     input_ready = false;
@@ -418,54 +541,134 @@ fn main() {
                                 }
                                 let n = parsed.unwrap();
 
-                                burt_gang.generations = n;
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.generations = n;
+                                    Ok(String::new())
+                                });
                             }
                             "survival_rate" => {
-                                let decimal = value.parse::<f32>();
-                                if decimal.is_err() {
-                                    footer_txt = format!("Invalid value: survival_rate expects a value between 0 and 1!");
-                                    footer_col = Color::LightRed;
-                                    error_start = Some(Instant::now());
-                                    input_ready = false;
-                                    user_input = String::new();
-                                    continue;
-                                }
-                                let n = decimal.unwrap();
-                                if n < 0.0 || n >= 1.0 {
-                                    footer_txt = format!("Invalid value: survival_rate expects a value between 0 and 1!");
-                                    footer_col = Color::LightRed;
-                                    error_start = Some(Instant::now());
-                                    input_ready = false;
-                                    user_input = String::new();
-                                    continue;
-                                }
-                                burt_gang.survival_rate = n;
+                                let n = match value.parse::<f32>() {
+                                    Ok(n) if check_rate("survival rate", n).is_ok() => n,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: survival_rate expects a value between 0 and 1, not including 0 and 1!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.survival_rate = n;
+                                    Ok(String::new())
+                                });
                             }
                             "mutation_rate" => {
-                                let decimal = value.parse::<f32>();
-                                if decimal.is_err() {
-                                    footer_txt = format!("Invalid value: mutation_rate expects a value between 0 and 1!");
-                                    footer_col = Color::LightRed;
-                                    error_start = Some(Instant::now());
-                                    input_ready = false;
-                                    user_input = String::new();
-                                    continue;
-                                }
-                                let n = decimal.unwrap();
-                                if n < 0.0 || n >= 1.0 {
-                                    footer_txt = format!("Invalid value: mutation_rate expects a value between 0 and 1!");
+                                let n = match value.parse::<f32>() {
+                                    Ok(n) if check_rate("mutation rate", n).is_ok() => n,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: mutation_rate expects a value between 0 and 1, not including 0 and 1!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.mutation_rate = n;
+                                    Ok(String::new())
+                                });
+                            }
+                            "burt_count" => {
+                                let n = match value.parse::<u32>() {
+                                    Ok(n) if check_burt_count(n).is_ok() => n,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: burt_count expects a value above 0!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.populate(n, false);
+                                    Ok(String::new())
+                                });
+                            }
+                            "crossover_rate" => {
+                                let rate = match value.parse::<f32>() {
+                                    Ok(rate) if check_crossover_rate(rate).is_ok() => rate,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: crossover_rate expects a value from 0 to 1!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.crossover_rate = rate;
+                                    Ok(String::new())
+                                });
+                            }
+                            "elite_count" => {
+                                let parsed = value.parse::<u32>();
+                                if parsed.is_err() {
+                                    footer_txt = String::from("Invalid value: elite_count expects a number of Burts!");
                                     footer_col = Color::LightRed;
                                     error_start = Some(Instant::now());
                                     input_ready = false;
                                     user_input = String::new();
                                     continue;
                                 }
-                                burt_gang.mutation_rate = n;
+                                let n = parsed.unwrap();
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.elite_count = n;
+                                    Ok(String::new())
+                                });
                             }
-                            "burt_count" => {
+                            "samples_per_eval" => {
+                                let samples = match value.parse::<u32>() {
+                                    Ok(samples) if check_samples_per_eval(samples).is_ok() => samples,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: samples_per_eval expects a value above 0!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.samples_per_eval = samples;
+                                    Ok(String::new())
+                                });
+                            }
+                            "islands" => {
+                                let islands = match value.parse::<usize>() {
+                                    Ok(islands) if check_islands(islands).is_ok() => islands,
+                                    _ => {
+                                        footer_txt = String::from("Invalid value: islands expects a value above 0!");
+                                        footer_col = Color::LightRed;
+                                        error_start = Some(Instant::now());
+                                        input_ready = false;
+                                        user_input = String::new();
+                                        continue;
+                                    }
+                                };
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.set_islands(islands);
+                                    info!(target:"MaLB", "Split the burts into {} islands", islands);
+                                    Ok(String::new())
+                                });
+                            }
+                            "checkpoint_every" => {
                                 let parsed = value.parse::<u32>();
                                 if parsed.is_err() {
-                                    footer_txt = format!("Invalid value: target expects a value above 0!");
+                                    footer_txt = String::from("Invalid value: checkpoint_every expects a number of generations (0 is off)!");
                                     footer_col = Color::LightRed;
                                     error_start = Some(Instant::now());
                                     input_ready = false;
@@ -473,10 +676,13 @@ fn main() {
                                     continue;
                                 }
                                 let n = parsed.unwrap();
-                                burt_gang.burts = populate_burts(n, burt_gang.range, false);
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.checkpoint_every = n;
+                                    Ok(String::new())
+                                });
                             }
                             _ => {
-                                footer_txt = format!("Invalid variable!");
+                                footer_txt = String::from("Invalid variable!");
                                 footer_col = Color::LightRed;
                                 error_start = Some(Instant::now());
                                 input_ready = false;
@@ -485,15 +691,287 @@ fn main() {
                             }
                         }
                     }
+                    "crossover" => {
+                        match parse_crossover(&cmd_args.join(" ")) {
+                            Ok(crossover) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.crossover = crossover;
+                                    info!(target:"MaLB", "Switched to {} crossover", burt_gang.crossover.name());
+                                    Ok(format!("{} crossover activated", burt_gang.crossover.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "mutation" => {
+                        match parse_mutation(&cmd_args.join(" ")) {
+                            Ok(mutation) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.mutation = mutation;
+                                    info!(target:"MaLB", "Switched to {} mutation", burt_gang.mutation.name());
+                                    Ok(format!("{} mutation activated", burt_gang.mutation.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "save" => {
+                        let path = cmd_args.join(" ");
+                        if path.is_empty() {
+                            footer_txt = String::from("save takes a file name!");
+                            footer_col = Color::LightRed;
+                            error_start = Some(Instant::now());
+                        } else {
+                            trainer.change(move |burt_gang| match burt_gang.save(&path) {
+                                Ok(()) => {
+                                    info!(target:"MaLB", "Saved generation {} to {}", burt_gang.current_generation, path);
+                                    Ok(format!("Saved to {}", path))
+                                }
+                                Err(e) => {
+                                    error!(target:"MaLB", "Failed to save to {}: {}", path, e);
+                                    Err(format!("Failed to save to {}: {}", path, e))
+                                }
+                            });
+                        }
+                    }
+                    "save_best" => {
+                        let path = cmd_args.join(" ");
+                        if path.is_empty() {
+                            footer_txt = String::from("save_best takes a file name!");
+                            footer_col = Color::LightRed;
+                            error_start = Some(Instant::now());
+                        } else {
+                            trainer.change(move |burt_gang| match burt_gang.save_best_average(&path) {
+                                Ok(()) => Ok(format!("Added the best average to {}", path)),
+                                Err(e) => Err(format!("Failed to save the best average to {}: {}", path, e)),
+                            });
+                        }
+                    }
+                    "load" => {
+                        let path = cmd_args.join(" ");
+                        if path.is_empty() {
+                            footer_txt = String::from("load takes a file name!");
+                            footer_col = Color::LightRed;
+                            error_start = Some(Instant::now());
+                        } else {
+                            burt_list_state.select(Some(0));
+                            trainer.change(move |burt_gang| match BurtGang::load(&path) {
+                                Ok(mut loaded) => {
+                                    // keep checkpointing the way it was set up for this session
+                                    loaded.checkpoint_every = burt_gang.checkpoint_every;
+                                    loaded.checkpoint_path = burt_gang.checkpoint_path.clone();
+                                    *burt_gang = loaded;
+                                    info!(target:"MaLB", "Loaded generation {} from {}", burt_gang.current_generation, path);
+                                    Ok(format!("Loaded {}", path))
+                                }
+                                Err(e) => {
+                                    error!(target:"MaLB", "Failed to load {}: {}", path, e);
+                                    Err(format!("Failed to load {}: {}", path, e))
+                                }
+                            });
+                        }
+                    }
+                    "schedule" => {
+                        match parse_schedule(&cmd_args.join(" ")) {
+                            Ok(schedule) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.schedule = schedule;
+                                    info!(target:"MaLB", "Switched to the {} target schedule", burt_gang.schedule.name());
+                                    Ok(format!("{} target schedule activated", burt_gang.schedule.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "fitness" => {
+                        match parse_fitness(&cmd_args.join(" ")) {
+                            Ok(fitness) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.fitness = fitness;
+                                    info!(target:"MaLB", "Switched to {} fitness", burt_gang.fitness.name());
+                                    Ok(format!("{} fitness activated", burt_gang.fitness.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "genome" => {
+                        match parse_genome(&cmd_args.join(" ")) {
+                            Ok(genome) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.set_genome(genome);
+                                    info!(target:"MaLB", "Switched to the {} genome, every burt was replaced", burt_gang.genome.name());
+                                    Ok(format!("{} genome activated, every burt was replaced", burt_gang.genome.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "boundary" => {
+                        match cmd_args.join(" ").parse::<Boundary>() {
+                            Ok(boundary) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.boundary = boundary;
+                                    info!(target:"MaLB", "Switched to the {} boundary", boundary);
+                                    Ok(format!("{} boundary activated", boundary))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "island" => {
+                        match (cmd_args.first().map(|i| i.parse::<usize>()), cmd_args.get(1), cmd_args.get(2)) {
+                            (Some(Ok(island)), Some(var), Some(_)) => {
+                                let var = var.to_string();
+                                let value = cmd_args[2..].join(" ");
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.set_island(island, &var, &value)?;
+                                    info!(target:"MaLB", "Island {} now trains with {} {}", island, var, value);
+                                    Ok(format!("Island {} {} set to {}", island, var, value))
+                                });
+                            }
+                            _ => {
+                                footer_txt = String::from("island takes an island number, a variable name and a new value!");
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "migration" => {
+                        match parse_migration(&cmd_args.join(" ")) {
+                            Ok(migration) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.migration = migration;
+                                    info!(target:"MaLB", "Switched to {} migration", migration.name());
+                                    Ok(format!("{} migration activated", migration.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "sharing" => {
+                        match parse_sharing(&cmd_args.join(" ")) {
+                            Ok(sharing) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.sharing = sharing;
+                                    info!(target:"MaLB", "Switched to {} fitness sharing", sharing.name());
+                                    Ok(format!("{} fitness sharing activated", sharing.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "stop_when" => {
+                        match parse_stop_conditions(&cmd_args.join(" ")) {
+                            Ok(conditions) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.stop_when = conditions;
+                                    let name = stop_conditions_name(&burt_gang.stop_when);
+                                    info!(target:"MaLB", "Training now stops when: {}", name);
+                                    Ok(format!("Training now stops when: {}", name))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "step" => {
+                        match cmd_args.first().map_or(Ok(1), |n| n.parse::<u32>()) {
+                            Ok(n) if n > 0 => trainer.send(Command::Step(n)),
+                            _ => {
+                                footer_txt = String::from("step takes a number of generations above 0!");
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
+                    }
+                    "delay" => {
+                        match cmd_args.first().map(|ms| ms.parse::<u64>()) {
+                            Some(Ok(ms)) => {
+                                trainer.send(Command::Delay(Duration::from_millis(ms)));
+                                footer_txt = if ms == 0 {
+                                    String::from("Training as fast as possible")
+                                } else {
+                                    format!("Training a generation every {}ms", ms)
+                                };
+                                footer_col = Color::LightYellow;
+                            }
+                            _ => {
+                                footer_txt = String::from("delay takes the milliseconds between generations (0 is as fast as possible)!");
+                                footer_col = Color::LightRed;
+                            }
+                        }
+                        error_start = Some(Instant::now());
+                    }
+                    "converge" => {
+                        match cmd_args.first().map_or(Ok(DEFAULT_CONVERGE_PATIENCE), |n| n.parse::<u32>()) {
+                            Ok(patience) if patience > 0 => {
+                                trainer.send(Command::Converge(patience));
+                                footer_txt = format!("Training until the average score stops improving for {} generations", patience);
+                                footer_col = Color::LightYellow;
+                            }
+                            _ => {
+                                footer_txt = String::from("converge takes a number of generations above 0!");
+                                footer_col = Color::LightRed;
+                            }
+                        }
+                        error_start = Some(Instant::now());
+                    }
                     "mode" => {
                         let mode = cmd_args.join(" ");
 
-                        footer_txt = format!("{} mode activated", mode);
-                        footer_col = Color::LightYellow;
-                        error_start = Some(Instant::now());
+                        match parse_selection(&mode) {
+                            Ok(selection) => {
+                                trainer.change(move |burt_gang| {
+                                    burt_gang.selection = selection;
+                                    info!(target:"MaLB", "Switched to {} selection", burt_gang.selection.name());
+                                    Ok(format!("{} mode activated", burt_gang.selection.name()))
+                                });
+                            }
+                            Err(e) => {
+                                footer_txt = e;
+                                footer_col = Color::LightRed;
+                                error_start = Some(Instant::now());
+                            }
+                        }
                     }
                     _ => {
-                        footer_txt = format!("Invalid Command!");
+                        footer_txt = String::from("Invalid Command!");
                         footer_col = Color::LightRed;
                         error_start = Some(Instant::now());
                     }
@@ -509,12 +987,21 @@ fn main() {
             if start.elapsed() >= error_time {
                 error_start = None;
                 footer_txt = default_footer_txt.clone();
-                footer_col = default_footer_col.clone();
+                footer_col = default_footer_col;
             }
         }
 
     }
 
+    // keep the latest generation when quitting with checkpoints on
+    let finished = trainer.stop();
+    let burt_gang = &finished.burt_gang;
+    if burt_gang.checkpoint_every > 0 {
+        if let Err(e) = burt_gang.save(&burt_gang.checkpoint_path) {
+            error!(target:"MaLB", "Failed to save checkpoint to {}: {}", burt_gang.checkpoint_path.display(), e);
+        }
+    }
+
     // restore terminal
     disable_raw_mode().expect("Failed to restore terminal");
     terminal.clear().expect("Failed to restore terminal");
@@ -527,4 +1014,12 @@ fn main() {
     ).expect("Failed to restore terminal");
     terminal.show_cursor().expect("Failed to restore terminal");
 
+    // the report goes on the normal screen, where it stays after the program exits
+    let report = run_report(burt_gang, finished.trained_for, Some(session_start.elapsed()), finished.stop_reason.as_deref());
+    print!("{}", report);
+    if let Some(path) = &args.report {
+        if let Err(e) = fs::write(path, &report) {
+            eprintln!("Failed to write the report to {}: {}", path.display(), e);
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,150 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Distribution, Normal};
+use crate::burt::split_spec;
+
+/// changes the genes of a new burt so the gang keeps trying new things
+pub trait Mutation: Send + Sync {
+    /// includes anything the mutation has adapted, i.e. "adaptive:0.67", so a saved gang carries on with it
+    fn name(&self) -> String;
+
+    /// mutates the genes in place, keeping every gene between 0 and `range`
+    fn mutate(&self, genes: &mut [f32], mutation_rate: f32, range: f32, rng: &mut StdRng);
+
+    /// called every generation with how many of the mutated burts did better than their parent
+    fn adapt(&mut self, _improved: usize, _mutated: usize) {}
+}
+
+/// moves every gene up or down by a uniform amount up to `range * mutation_rate`, the original behaviour
+pub struct UniformMutation;
+
+impl Mutation for UniformMutation {
+    fn name(&self) -> String {
+        String::from("uniform")
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        // todo(eric): This means that theoretically a generation can go by with no mutation
+        let max_amt = range * mutation_rate;
+        if max_amt <= 0.0 {
+            return;
+        }
+        for gene in genes {
+            let mut_amt = rng.gen_range(0.0..max_amt);
+            if rng.gen_bool(0.5) {
+                *gene = (*gene + mut_amt).min(range);
+            } else {
+                *gene = (*gene - mut_amt).max(0.0);
+            }
+        }
+    }
+}
+
+// adds normal noise with a standard deviation of `step` to the gene
+fn perturb(gene: &mut f32, step: f32, range: f32, rng: &mut StdRng) {
+    if let Ok(normal) = Normal::new(0.0, step) {
+        *gene = (*gene + normal.sample(rng)).clamp(0.0, range);
+    }
+}
+
+/// adds normal noise to every gene, with a standard deviation of `range * mutation_rate`
+pub struct GaussianMutation;
+
+impl Mutation for GaussianMutation {
+    fn name(&self) -> String {
+        String::from("gaussian")
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        for gene in genes {
+            perturb(gene, range * mutation_rate, range, rng);
+        }
+    }
+}
+
+/// only mutates each gene with a chance of `probability`, using another mutation for the genes that are picked
+pub struct PerGeneMutation {
+    pub probability: f64,
+    pub inner: Box<dyn Mutation>,
+}
+
+impl Mutation for PerGeneMutation {
+    fn name(&self) -> String {
+        format!("gene:{}:{}", self.probability, self.inner.name())
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        for x in 0..genes.len() {
+            if rng.gen_bool(self.probability) {
+                self.inner.mutate(&mut genes[x..=x], mutation_rate, range, rng);
+            }
+        }
+    }
+
+    fn adapt(&mut self, improved: usize, mutated: usize) {
+        self.inner.adapt(improved, mutated);
+    }
+}
+
+/// gaussian mutation with a step size that follows the 1/5th success rule:
+/// if more than a fifth of the mutations improved on their parent the step grows, otherwise it shrinks
+pub struct AdaptiveMutation {
+    // multiplies the standard deviation of `range * mutation_rate`
+    pub step: f32,
+}
+
+impl AdaptiveMutation {
+    const SUCCESS_RATE: f32 = 0.2;
+    const SHRINK: f32 = 0.82;
+    const MIN_STEP: f32 = 0.001;
+    const MAX_STEP: f32 = 1000.0;
+}
+
+impl Mutation for AdaptiveMutation {
+    fn name(&self) -> String {
+        format!("adaptive:{}", self.step)
+    }
+
+    fn mutate(&self, genes: &mut [f32], mutation_rate: f32, range: f32, rng: &mut StdRng) {
+        for gene in genes {
+            perturb(gene, range * mutation_rate * self.step, range, rng);
+        }
+    }
+
+    fn adapt(&mut self, improved: usize, mutated: usize) {
+        let success_rate = improved as f32 / mutated as f32;
+        if success_rate > Self::SUCCESS_RATE {
+            self.step /= Self::SHRINK;
+        } else if success_rate < Self::SUCCESS_RATE {
+            self.step *= Self::SHRINK;
+        }
+        self.step = self.step.clamp(Self::MIN_STEP, Self::MAX_STEP);
+    }
+}
+
+/// parses a mutation from "uniform", "gaussian", "adaptive[:step]" or "gene:probability[:mutation]"
+pub fn parse_mutation(spec: &str) -> Result<Box<dyn Mutation>, String> {
+    let spec = spec.trim().to_ascii_lowercase().replace(' ', ":");
+    let (name, arg) = split_spec(&spec);
+
+    match (name, arg) {
+        ("uniform", None) => Ok(Box::new(UniformMutation)),
+        ("gaussian", None) => Ok(Box::new(GaussianMutation)),
+        ("adaptive", None) => Ok(Box::new(AdaptiveMutation { step: 1.0 })),
+        ("adaptive", Some(step)) => match step.parse::<f32>() {
+            Ok(step) if step > 0.0 => Ok(Box::new(AdaptiveMutation { step })),
+            _ => Err(String::from("The adaptive step must be above 0!")),
+        },
+        ("gene", Some(arg)) => {
+            let (probability, inner) = match arg.split_once(':') {
+                Some((probability, inner)) => (probability, parse_mutation(inner)?),
+                None => (arg, Box::new(GaussianMutation) as Box<dyn Mutation>),
+            };
+            match probability.parse::<f64>() {
+                Ok(probability) if (0.0..=1.0).contains(&probability) => Ok(Box::new(PerGeneMutation { probability, inner })),
+                _ => Err(String::from("The gene mutation probability must be between 0 and 1!")),
+            }
+        }
+        _ => Err(format!("Unknown mutation: {} (expected uniform, gaussian, adaptive[:step] or gene:probability[:mutation])", spec)),
+    }
+}
@@ -0,0 +1,135 @@
+use std::fmt::Write;
+use std::time::Duration;
+use crate::burt::{BurtGang, GenerationStats};
+
+// the size of the convergence plot in the report, in characters
+const PLOT_WIDTH: usize = 60;
+const PLOT_HEIGHT: usize = 12;
+
+/// what a run did, for printing once it's over and for `--report`. `trained_for` is the time spent training,
+/// `total` how long the whole session took (if it's different), and `stop_reason` how training ended by itself
+pub fn run_report(burt_gang: &BurtGang, trained_for: Duration, total: Option<Duration>, stop_reason: Option<&str>) -> String {
+    let history = burt_gang.history();
+    let range = burt_gang.range as f32;
+    // writing to a string can't fail
+    let mut report = String::new();
+
+    writeln!(report, "MaLB run report").unwrap();
+    write!(report, "{}", burt_gang).unwrap();
+
+    match (history.first(), history.last()) {
+        (Some(first), Some(last)) => writeln!(report, "Generations run:     {} (generation {} to {} of {})",
+                                              history.len(), first.generation, last.generation, burt_gang.generations),
+        _ => writeln!(report, "Generations run:     0"),
+    }.unwrap();
+    match total {
+        Some(total) => writeln!(report, "Time taken:          {} training, {} in total", duration(trained_for), duration(total)),
+        None => writeln!(report, "Time taken:          {}", duration(trained_for)),
+    }.unwrap();
+    match stop_reason {
+        Some(reason) => writeln!(report, "Stopped:             {}", reason),
+        None => writeln!(report, "Stopped:             quit at generation {}/{}", burt_gang.current_generation, burt_gang.generations),
+    }.unwrap();
+    match history.iter().find(|stats| stats.perfect_count > 0) {
+        Some(stats) => writeln!(report, "First perfect guess: generation {}", stats.generation),
+        None => writeln!(report, "First perfect guess: never"),
+    }.unwrap();
+    match burt_gang.best_burt() {
+        Some(best) => writeln!(report, "Best burt:           #{} {} (score {}, guess {})", best.get_id(),
+                               burt_gang.genome.describe(best.genes(), range), best.get_score_display(), best.get_guess_display()),
+        None => writeln!(report, "Best burt:           ? (the gang hasn't been trained)"),
+    }.unwrap();
+    if let Some(genes) = burt_gang.best_average() {
+        writeln!(report, "Best average:        {}", burt_gang.genome.describe(&genes, range)).unwrap();
+    }
+
+    writeln!(report).unwrap();
+    writeln!(report, "Average score by generation:").unwrap();
+    report.push_str(&convergence_plot(history, PLOT_WIDTH, PLOT_HEIGHT));
+    report
+}
+
+// i.e. "1h 2m 3s", "2m 3s" or "3.2s"
+fn duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{:.1}s", duration.as_secs_f32()),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {}m {}s", seconds / 3600, seconds / 60 % 60, seconds % 60),
+    }
+}
+
+// the mean score of every generation drawn with plain characters, at most `width` columns wide.
+// when there are more generations than columns, each column is the average of the generations it covers
+fn convergence_plot(history: &[GenerationStats], width: usize, height: usize) -> String {
+    if history.is_empty() {
+        return String::from("(no generations trained)\n");
+    }
+    let columns = width.min(history.len());
+    let points: Vec<f32> = (0..columns)
+        .map(|column| {
+            let generations = &history[column * history.len() / columns..(column + 1) * history.len() / columns];
+            generations.iter().map(|stats| stats.mean_score).sum::<f32>() / generations.len() as f32
+        })
+        .collect();
+
+    let bottom = points.iter().copied().fold(f32::MAX, f32::min);
+    let top = points.iter().copied().fold(f32::MIN, f32::max).max(bottom + 1.0);
+    let rows: Vec<usize> = points.iter()
+        .map(|point| ((point - bottom) / (top - bottom) * (height - 1) as f32).round() as usize)
+        .collect();
+
+    // the top and bottom rows are labelled with their score
+    let (top_label, bottom_label) = (format!("{:.1}", top), format!("{:.1}", bottom));
+    let label_width = top_label.len().max(bottom_label.len());
+    let mut plot = String::new();
+    for row in (0..height).rev() {
+        let (label, axis) = match row {
+            row if row == height - 1 => (top_label.as_str(), '+'),
+            0 => (bottom_label.as_str(), '+'),
+            _ => ("", '|'),
+        };
+        let line: String = rows.iter().map(|r| if *r == row { '*' } else { ' ' }).collect();
+        writeln!(plot, "{:>w$} {}{}", label, axis, line.trim_end(), w = label_width).unwrap();
+    }
+    writeln!(plot, "{:>w$} +{}", "", "-".repeat(columns), w = label_width).unwrap();
+
+    // the first and last generation under the ends of the axis
+    let first = history[0].generation.to_string();
+    let last = history[history.len() - 1].generation.to_string();
+    let gap = columns.saturating_sub(first.len() + last.len()).max(1);
+    writeln!(plot, "{:>w$}  {}{}{}", "", first, " ".repeat(gap), last, w = label_width).unwrap();
+    plot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+
+    #[test]
+    fn reports_a_trained_gang() {
+        let mut burt_gang = seeded_gang(5);
+        burt_gang.generations = 200;
+        let report = run_report(&burt_gang, Duration::ZERO, None, None);
+        assert!(report.contains("Generations run:     0"));
+        assert!(report.contains("(no generations trained)"));
+
+        for _ in 0..100 {
+            burt_gang.train(false);
+        }
+        let report = run_report(&burt_gang, Duration::from_secs(75), Some(Duration::from_secs(3725)), Some("Converged at generation 100"));
+        assert!(report.contains("Generations run:     100 (generation 1 to 100 of 200)"));
+        assert!(report.contains("Time taken:          1m 15s training, 1h 2m 5s in total"));
+        assert!(report.contains("Stopped:             Converged at generation 100"));
+        assert!(report.contains("Best burt:           #"));
+
+        // one point per column, the first generation has the worst score so it's on the top row
+        let plot = convergence_plot(burt_gang.history(), 50, 8);
+        let lines: Vec<&str> = plot.lines().collect();
+        assert_eq!(lines.len(), 8 + 2);
+        assert_eq!(lines.iter().take(8).map(|line| line.matches('*').count()).sum::<usize>(), 50);
+        assert!(lines[0].contains("+*"));
+        assert!(lines[9].trim_start().starts_with('1') && lines[9].ends_with("100"));
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use rand::Rng;
+use rand::rngs::StdRng;
+use rand_distr::{Exp1, StandardNormal};
+
+// how many draws a truncated sample gets before it settles for the nearest bound,
+// the samplers below almost never need more than a handful
+const MAX_TRIES: u32 = 1000;
+
+/// what happens to a guess that falls outside of the range
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Boundary {
+    /// moved to the nearest end of the range
+    Clamp,
+    /// bounced back into the range by however far it went past the end
+    Reflect,
+    /// drawn again, so the guesses follow the distribution cut down to the range
+    #[default]
+    Truncate,
+}
+
+impl Boundary {
+    /// keeps a draw in `low..=high`, `redraw` is only called to truncate
+    pub fn apply(&self, x: f32, low: f32, high: f32, mut redraw: impl FnMut() -> f32) -> f32 {
+        if x.is_nan() {
+            return low;
+        }
+        match self {
+            Boundary::Clamp => x.clamp(low, high),
+            Boundary::Reflect => reflect(x, low, high),
+            Boundary::Truncate => {
+                let mut x = x;
+                for _ in 0..MAX_TRIES {
+                    if (low..=high).contains(&x) {
+                        return x;
+                    }
+                    x = redraw();
+                }
+                // the distribution barely touches the range, the nearest bound is as close as it gets
+                if x.is_nan() { low } else { x.clamp(low, high) }
+            }
+        }
+    }
+}
+
+impl Display for Boundary {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Boundary::Clamp => "clamp",
+            Boundary::Reflect => "reflect",
+            Boundary::Truncate => "truncate",
+        })
+    }
+}
+
+impl FromStr for Boundary {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "clamp" => Ok(Boundary::Clamp),
+            "reflect" => Ok(Boundary::Reflect),
+            "truncate" => Ok(Boundary::Truncate),
+            _ => Err(format!("Unknown boundary: {} (expected clamp, reflect or truncate)", s)),
+        }
+    }
+}
+
+// folds x back and forth over the range until it lands in it
+fn reflect(x: f32, low: f32, high: f32) -> f32 {
+    let width = high - low;
+    if width <= 0.0 || !x.is_finite() {
+        return x.clamp(low, high);
+    }
+    let folded = (x - low).rem_euclid(2.0 * width);
+    low + if folded > width { 2.0 * width - folded } else { folded }
+}
+
+/// a draw from a normal distribution with the boundary applied to keep it in `low..=high`.
+/// a sigma of 0, below 0 or NaN can't spread the guesses at all, so the guess is mu (kept in the range)
+pub fn bounded_normal(mu: f32, sigma: f32, low: f32, high: f32, boundary: Boundary, rng: &mut StdRng) -> f32 {
+    if mu.is_nan() {
+        return low;
+    }
+    if !(sigma > 0.0 && sigma.is_finite()) {
+        return mu.clamp(low, high);
+    }
+    match boundary {
+        Boundary::Truncate => truncated_normal(mu, sigma, low, high, rng),
+        boundary => {
+            let z: f32 = rng.sample(StandardNormal);
+            boundary.apply(mu + sigma * z, low, high, || unreachable!("only truncating redraws"))
+        }
+    }
+}
+
+// a normal distribution cut down to low..=high, drawn with whichever proposal accepts often for where the range is:
+// the normal itself for a wide range around mu, a uniform over a narrow range, and an exponential
+// for a range out in a tail (Robert, 1995). every proposal accepts a good share of its draws,
+// and there's a limit on the tries anyway
+fn truncated_normal(mu: f32, sigma: f32, low: f32, high: f32, rng: &mut StdRng) -> f32 {
+    // the range in standard deviations from mu
+    let a = ((low - mu) / sigma) as f64;
+    let b = ((high - mu) / sigma) as f64;
+    // a range entirely below mu is the mirror image of one above it
+    let (a, b, flip) = if b < 0.0 { (-b, -a, -1.0) } else { (a, b, 1.0) };
+
+    for _ in 0..MAX_TRIES {
+        let z = if a <= 0.0 {
+            if b - a >= 2.5 {
+                // mu is in a wide range, most draws are already in it
+                let z: f64 = rng.sample(StandardNormal);
+                if z < a || z > b { continue; }
+                z
+            } else {
+                let z = rng.gen_range(a..=b);
+                if rng.gen::<f64>() > (-z * z / 2.0).exp() { continue; }
+                z
+            }
+        } else {
+            let rate = (a + (a * a + 4.0).sqrt()) / 2.0;
+            if b - a < 1.0 / rate {
+                // a narrow range in the tail
+                let z = rng.gen_range(a..=b);
+                if rng.gen::<f64>() > ((a * a - z * z) / 2.0).exp() { continue; }
+                z
+            } else {
+                let z = a + rng.sample::<f64, _>(Exp1) / rate;
+                if z > b || rng.gen::<f64>() > (-(z - rate) * (z - rate) / 2.0).exp() { continue; }
+                z
+            }
+        };
+        return (mu as f64 + sigma as f64 * flip * z).clamp(low as f64, high as f64) as f32;
+    }
+    mu.clamp(low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+
+    #[test]
+    fn every_boundary_stays_in_the_range() {
+        let mut rng = StdRng::seed_from_u64(1);
+        // mu in the range, far below it, far above it, a huge sigma and a range of a single value
+        for (mu, sigma, low, high) in [(50.0, 10.0, 0.0, 100.0), (-500.0, 20.0, 0.0, 100.0), (900.0, 5.0, 0.0, 100.0),
+                                       (0.0, 1e30, 0.0, 100.0), (3.0, 4.0, 7.0, 7.0)] {
+            for boundary in [Boundary::Clamp, Boundary::Reflect, Boundary::Truncate] {
+                for _ in 0..1000 {
+                    let x = bounded_normal(mu, sigma, low, high, boundary, &mut rng);
+                    assert!((low..=high).contains(&x), "{}: N({}, {}) gave {}", boundary, mu, sigma, x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn broken_sigmas_guess_mu() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for sigma in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            assert_eq!(bounded_normal(30.0, sigma, 0.0, 100.0, Boundary::Truncate, &mut rng), 30.0);
+            assert_eq!(bounded_normal(130.0, sigma, 0.0, 100.0, Boundary::Reflect, &mut rng), 100.0);
+        }
+        assert_eq!(bounded_normal(f32::NAN, 1.0, 0.0, 100.0, Boundary::Clamp, &mut rng), 0.0);
+    }
+
+    #[test]
+    fn truncating_keeps_the_shape() {
+        // cut down to the upper half, a normal's mean is mu + sigma * sqrt(2 / pi)
+        let mut rng = StdRng::seed_from_u64(3);
+        let n = 20000;
+        let mean = (0..n).map(|_| bounded_normal(0.0, 10.0, 0.0, 1000.0, Boundary::Truncate, &mut rng)).sum::<f32>() / n as f32;
+        assert!((mean - 7.98).abs() < 0.3, "mean {}", mean);
+
+        // far out in the tail everything piles up just past the bound
+        let tail = (0..n).map(|_| bounded_normal(0.0, 1.0, 5.0, 1000.0, Boundary::Truncate, &mut rng)).sum::<f32>() / n as f32;
+        assert!((tail - 5.19).abs() < 0.05, "tail mean {}", tail);
+
+        assert_eq!(reflect(105.0, 0.0, 100.0), 95.0);
+        assert_eq!(reflect(-5.0, 0.0, 100.0), 5.0);
+        assert_eq!("Reflect".parse::<Boundary>(), Ok(Boundary::Reflect));
+        assert!("wrap".parse::<Boundary>().is_err());
+    }
+}
@@ -0,0 +1,265 @@
+use rand::Rng;
+use rand::rngs::StdRng;
+use crate::burt::split_spec;
+
+/// something for the burts to guess, the target is the right answer for the input
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Example {
+    pub input: f32,
+    pub target: u32,
+}
+
+impl Example {
+    /// just a target, guessed with an input of 1 like the burts always used to
+    pub fn target(target: u32) -> Self {
+        Self { input: 1.0, target }
+    }
+}
+
+/// picks the examples the burts guess at each generation
+pub trait TargetSchedule: Send + Sync {
+    fn name(&self) -> String;
+
+    /// the examples of `generation` (the first is 1), the target of the first one becomes the gang's target.
+    /// every burt guesses once per example and is scored on its average distance. targets are below `range`
+    fn examples(&self, generation: u32, target: u32, range: u32, rng: &mut StdRng) -> Vec<Example>;
+}
+
+/// always the gang's target, the original behaviour
+pub struct FixedTarget;
+
+impl TargetSchedule for FixedTarget {
+    fn name(&self) -> String {
+        String::from("fixed")
+    }
+
+    fn examples(&self, _generation: u32, target: u32, _range: u32, _rng: &mut StdRng) -> Vec<Example> {
+        vec![Example::target(target)]
+    }
+}
+
+/// a new random target every generation
+pub struct RandomTarget;
+
+impl TargetSchedule for RandomTarget {
+    fn name(&self) -> String {
+        String::from("random")
+    }
+
+    fn examples(&self, _generation: u32, _target: u32, range: u32, rng: &mut StdRng) -> Vec<Example> {
+        vec![Example::target(rng.gen_range(0..range))]
+    }
+}
+
+/// goes through the targets one generation at a time, starting over at the end
+pub struct CycleTargets {
+    pub targets: Vec<u32>,
+}
+
+impl TargetSchedule for CycleTargets {
+    fn name(&self) -> String {
+        format!("cycle:{}", join(&self.targets))
+    }
+
+    fn examples(&self, generation: u32, _target: u32, _range: u32, _rng: &mut StdRng) -> Vec<Example> {
+        vec![Example::target(self.targets[generation.saturating_sub(1) as usize % self.targets.len()])]
+    }
+}
+
+/// a random walk, the target moves up or down by up to `step` every generation
+pub struct DriftTarget {
+    pub step: u32,
+}
+
+impl TargetSchedule for DriftTarget {
+    fn name(&self) -> String {
+        format!("drift:{}", self.step)
+    }
+
+    fn examples(&self, _generation: u32, target: u32, range: u32, rng: &mut StdRng) -> Vec<Example> {
+        let step = rng.gen_range(0..=self.step);
+        let target = if rng.gen_bool(0.5) { target.saturating_add(step) } else { target.saturating_sub(step) };
+        vec![Example::target(target.min(range - 1))]
+    }
+}
+
+/// every target, every generation, so the burts have to do well on all of them at once
+pub struct SequenceTargets {
+    pub targets: Vec<u32>,
+}
+
+impl TargetSchedule for SequenceTargets {
+    fn name(&self) -> String {
+        format!("sequence:{}", join(&self.targets))
+    }
+
+    fn examples(&self, _generation: u32, _target: u32, _range: u32, _rng: &mut StdRng) -> Vec<Example> {
+        self.targets.iter().map(|target| Example::target(*target)).collect()
+    }
+}
+
+/// a supervised task, `count` random whole inputs every generation and the burts have to answer
+/// `slope * input + intercept` for each of them. the linear genome can learn it, the others can only scale their guess
+pub struct FunctionTask {
+    pub slope: f32,
+    pub intercept: f32,
+    pub count: u32,
+}
+
+impl TargetSchedule for FunctionTask {
+    fn name(&self) -> String {
+        format!("function:{}x{:+}:{}", self.slope, self.intercept, self.count)
+    }
+
+    fn examples(&self, _generation: u32, _target: u32, range: u32, rng: &mut StdRng) -> Vec<Example> {
+        // only the inputs whose answer is in the range, so every answer can be guessed
+        let top = (range - 1) as f32;
+        let largest = if self.slope > 0.0 {
+            ((top - self.intercept) / self.slope).max(0.0) as u32
+        } else {
+            range - 1
+        };
+        (0..self.count).map(|_| {
+            let input = rng.gen_range(0..=largest) as f32;
+            let target = (self.slope * input + self.intercept).round().clamp(0.0, top) as u32;
+            Example { input, target }
+        }).collect()
+    }
+}
+
+fn join(targets: &[u32]) -> String {
+    targets.iter().map(|t| t.to_string()).collect::<Vec<_>>().join(",")
+}
+
+// parses "7,20,50" into at least one target
+fn parse_targets(list: &str) -> Result<Vec<u32>, String> {
+    list.split(',')
+        .map(|t| t.trim().parse::<u32>())
+        .collect::<Result<Vec<u32>, _>>()
+        .ok()
+        .filter(|targets| !targets.is_empty())
+        .ok_or_else(|| format!("Invalid targets: {} (expected a list like 7,20,50)", list))
+}
+
+// parses "3x+7" into the slope and intercept
+fn parse_function(function: &str) -> Result<(f32, f32), String> {
+    let invalid = || format!("Invalid function: {} (expected a line like 3x+7)", function);
+    let (slope, intercept) = function.split_once('x').ok_or_else(invalid)?;
+    let slope = match slope {
+        "" | "+" => 1.0,
+        "-" => -1.0,
+        slope => slope.parse::<f32>().map_err(|_| invalid())?,
+    };
+    let intercept = match intercept {
+        "" => 0.0,
+        intercept => intercept.trim_start_matches('+').parse::<f32>().map_err(|_| invalid())?,
+    };
+    if !slope.is_finite() || !intercept.is_finite() {
+        return Err(invalid());
+    }
+    Ok((slope, intercept))
+}
+
+/// parses a target schedule from "fixed", "random", "cycle:t1,t2,..", "drift[:step]", "sequence:t1,t2,.."
+/// or "function:ax+b[:count]"
+pub fn parse_schedule(spec: &str) -> Result<Box<dyn TargetSchedule>, String> {
+    let spec = spec.trim().to_ascii_lowercase();
+    let (name, arg) = split_spec(&spec);
+
+    match (name, arg) {
+        ("fixed", None) => Ok(Box::new(FixedTarget)),
+        ("random", None) => Ok(Box::new(RandomTarget)),
+        ("cycle", Some(list)) => Ok(Box::new(CycleTargets { targets: parse_targets(list)? })),
+        ("drift", None) => Ok(Box::new(DriftTarget { step: 1 })),
+        ("drift", Some(step)) => match step.parse::<u32>() {
+            Ok(step) => Ok(Box::new(DriftTarget { step })),
+            _ => Err(String::from("The drift step must be a whole number!")),
+        },
+        ("sequence", Some(list)) => Ok(Box::new(SequenceTargets { targets: parse_targets(list)? })),
+        ("function", Some(arg)) => {
+            let (function, count) = match arg.split_once([':', ' ']) {
+                Some((function, count)) => match count.trim().parse::<u32>() {
+                    Ok(count) if count > 0 => (function, count),
+                    _ => return Err(String::from("The number of examples must be at least 1!")),
+                },
+                None => (arg, 10),
+            };
+            let (slope, intercept) = parse_function(function)?;
+            Ok(Box::new(FunctionTask { slope, intercept, count }))
+        }
+        _ => Err(format!("Unknown target schedule: {} (expected fixed, random, cycle:t1,t2,.., drift[:step], sequence:t1,t2,.. \
+                          or function:ax+b[:count])", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use super::*;
+    use crate::burt::{BurtGang, check_target};
+    use crate::burt::tests::seeded_gang;
+    use crate::genome::parse_genome;
+
+    #[test]
+    fn schedules_move_the_target() {
+        let mut burt_gang = seeded_gang(4);
+        burt_gang.schedule = parse_schedule("cycle:3,9,27").unwrap();
+        for target in [3, 9, 27, 3] {
+            burt_gang.train(false);
+            assert_eq!(burt_gang.target, target);
+        }
+
+        burt_gang.schedule = parse_schedule("drift:5").unwrap();
+        for _ in 0..50 {
+            let last = burt_gang.target;
+            burt_gang.train(false);
+            assert!(burt_gang.target.abs_diff(last) <= 5 && burt_gang.target < burt_gang.range);
+        }
+
+        // every target is one the command line would take
+        let mut rng = StdRng::seed_from_u64(4);
+        for spec in ["random", "drift:50", "function:3x+7"] {
+            let schedule = parse_schedule(spec).unwrap();
+            for generation in 1..200 {
+                for example in schedule.examples(generation, 99, 100, &mut rng) {
+                    assert!(check_target(example.target, 100).is_ok(), "{} gave {}", spec, example.target);
+                }
+            }
+        }
+
+        // a sequence guesses all of its targets and shows the first one
+        burt_gang.schedule = parse_schedule("sequence:60,0,100").unwrap();
+        burt_gang.train(false);
+        assert_eq!(burt_gang.target, 60);
+        assert_eq!(burt_gang.schedule.name(), "sequence:60,0,100");
+
+        for bad in ["cycle", "cycle:1,x", "drift:-1", "sequence:", "later", "function", "function:3y+7", "function:3x+7:0"] {
+            assert!(parse_schedule(bad).is_err(), "{} should be rejected", bad);
+        }
+    }
+
+    #[test]
+    fn linear_burts_learn_a_function() {
+        let schedule = parse_schedule("function:3x+7").unwrap();
+        assert_eq!(schedule.name(), "function:3x+7:10");
+        let mut rng = StdRng::seed_from_u64(2);
+        for example in schedule.examples(1, 7, 100, &mut rng) {
+            assert_eq!(example.target as f32, 3.0 * example.input + 7.0);
+            assert!(example.target <= 100);
+        }
+        for (spec, name) in [("function:x", "function:1x+0:10"), ("function:0.5x-2:3", "function:0.5x-2:3")] {
+            assert_eq!(parse_schedule(spec).unwrap().name(), name);
+        }
+
+        let mut burt_gang = BurtGang::new(100, 7, 150, 0.25, 0.01, 9);
+        burt_gang.genome = parse_genome("linear").unwrap();
+        burt_gang.schedule = schedule;
+        burt_gang.populate(300, false);
+        for _ in 0..burt_gang.generations {
+            burt_gang.train(false);
+        }
+        assert!(burt_gang.best_score().unwrap() <= 2, "best score {:?}", burt_gang.best_score());
+        let slope = burt_gang.best_average().unwrap()[0];
+        assert!((slope - 3.0).abs() < 0.5, "slope {}", slope);
+    }
+}
@@ -0,0 +1,156 @@
+use std::time::Duration;
+use crate::burt::GenerationStats;
+
+/// a reason to stop training before the last generation
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StopCondition {
+    /// the best score has been 0 for this many generations in a row
+    Perfect(u32),
+    /// the average score is below this
+    AverageBelow(f32),
+    /// the average score hasn't improved in this many generations
+    Stagnant(u32),
+    /// training has taken this long, paused time doesn't count
+    TimeLimit(Duration),
+}
+
+impl StopCondition {
+    /// the way `parse_stop_conditions` takes it, times are always in seconds
+    pub fn name(&self) -> String {
+        match self {
+            StopCondition::Perfect(generations) => format!("perfect:{}", generations),
+            StopCondition::AverageBelow(score) => format!("average:{}", score),
+            StopCondition::Stagnant(generations) => format!("stagnant:{}", generations),
+            StopCondition::TimeLimit(limit) => format!("time:{}s", limit.as_secs()),
+        }
+    }
+
+    /// why training should stop now, if it should
+    pub fn check(&self, progress: &StopProgress, trained_for: Duration) -> Option<String> {
+        match *self {
+            StopCondition::Perfect(generations) if progress.perfect_streak >= generations =>
+                Some(format!("the best score has been 0 for {} generations", progress.perfect_streak)),
+            StopCondition::AverageBelow(score) => progress.average_score
+                .filter(|average| *average < score)
+                .map(|average| format!("the average score {:.2} is below {}", average, score)),
+            StopCondition::Stagnant(generations) if progress.stale >= generations =>
+                Some(format!("the average score hasn't improved in {} generations", progress.stale)),
+            StopCondition::TimeLimit(limit) if trained_for >= limit =>
+                Some(format!("training has taken {}s, the limit is {}s", trained_for.as_secs(), limit.as_secs())),
+            _ => None,
+        }
+    }
+}
+
+/// parses a comma separated list of "perfect:N", "average:SCORE", "stagnant:N" and "time:SECONDS"
+/// (or time:Nm / time:Nh), "none" is no conditions at all
+pub fn parse_stop_conditions(spec: &str) -> Result<Vec<StopCondition>, String> {
+    let spec = spec.trim().to_ascii_lowercase().replace(' ', "");
+    if spec == "none" {
+        return Ok(Vec::new());
+    }
+    spec.split(',').map(|condition| {
+        let (name, value) = condition.split_once(':')
+            .ok_or_else(|| format!("Invalid stop condition: {} (expected perfect:N, average:SCORE, stagnant:N or time:SECONDS)", condition))?;
+        let generations = || match value.parse::<u32>() {
+            Ok(generations) if generations > 0 => Ok(generations),
+            _ => Err(format!("{} takes a number of generations above 0!", name)),
+        };
+        match name {
+            "perfect" => Ok(StopCondition::Perfect(generations()?)),
+            "stagnant" => Ok(StopCondition::Stagnant(generations()?)),
+            "average" => match value.parse::<f32>() {
+                Ok(score) if score > 0.0 => Ok(StopCondition::AverageBelow(score)),
+                _ => Err(String::from("average takes a score above 0!")),
+            },
+            "time" => {
+                let (number, unit) = match value.char_indices().last() {
+                    Some((x, unit @ ('s' | 'm' | 'h'))) => (&value[..x], unit),
+                    _ => (value, 's'),
+                };
+                match number.parse::<u64>() {
+                    Ok(number) if number > 0 => Ok(StopCondition::TimeLimit(Duration::from_secs(match unit {
+                        'h' => number * 3600,
+                        'm' => number * 60,
+                        _ => number,
+                    }))),
+                    _ => Err(String::from("time takes a number of seconds above 0 (or minutes with m, hours with h)!")),
+                }
+            }
+            _ => Err(format!("Unknown stop condition: {} (expected perfect, average, stagnant or time)", name)),
+        }
+    }).collect()
+}
+
+/// the name of a list of conditions, the way `parse_stop_conditions` takes it
+pub fn stop_conditions_name(conditions: &[StopCondition]) -> String {
+    if conditions.is_empty() {
+        return String::from("none");
+    }
+    conditions.iter().map(|condition| condition.name()).collect::<Vec<String>>().join(",")
+}
+
+/// what the stop conditions need to know about the generations trained so far
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StopProgress {
+    // generations in a row with a perfect best burt
+    pub perfect_streak: u32,
+    pub average_score: Option<f32>,
+    best_average: Option<f32>,
+    // generations since the average score last improved
+    pub stale: u32,
+}
+
+impl StopProgress {
+    /// takes in the stats of the generation just trained
+    pub fn update(&mut self, stats: &GenerationStats) {
+        self.perfect_streak = if stats.min_score == 0 { self.perfect_streak + 1 } else { 0 };
+        self.average_score = Some(stats.mean_score);
+        if self.best_average.is_none_or(|best| stats.mean_score < best) {
+            self.best_average = Some(stats.mean_score);
+            self.stale = 0;
+        } else {
+            self.stale += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::burt::tests::seeded_gang;
+
+    #[test]
+    fn every_stop_condition_stops() {
+        for spec in ["perfect:3", "average:2", "stagnant:4"] {
+            let mut burt_gang = seeded_gang(23);
+            burt_gang.generations = 500;
+            burt_gang.stop_when = parse_stop_conditions(spec).unwrap();
+            assert_eq!(stop_conditions_name(&burt_gang.stop_when), spec);
+            while burt_gang.stop_reason(Duration::ZERO).is_none() {
+                assert!(burt_gang.current_generation < burt_gang.generations, "{} never stopped", spec);
+                burt_gang.train(false);
+            }
+
+            let history = burt_gang.history();
+            let last = history.last().unwrap();
+            match burt_gang.stop_when[0] {
+                StopCondition::Perfect(n) => assert!(history.iter().rev().take(n as usize).all(|stats| stats.min_score == 0)),
+                StopCondition::AverageBelow(score) => assert!(last.mean_score < score),
+                StopCondition::Stagnant(n) => {
+                    let before = &history[..history.len() - n as usize];
+                    let best = before.iter().map(|stats| stats.mean_score).fold(f32::MAX, f32::min);
+                    assert!(history[before.len()..].iter().all(|stats| stats.mean_score >= best));
+                }
+                StopCondition::TimeLimit(_) => unreachable!(),
+            }
+        }
+
+        let mut burt_gang = seeded_gang(23);
+        burt_gang.stop_when = parse_stop_conditions("time:1m").unwrap();
+        assert!(burt_gang.stop_reason(Duration::from_secs(59)).is_none());
+        assert!(burt_gang.stop_reason(Duration::from_secs(60)).is_some());
+        assert_eq!(parse_stop_conditions("none").unwrap(), Vec::new());
+        assert!(parse_stop_conditions("time:0").is_err());
+    }
+}
@@ -0,0 +1,462 @@
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use log::{error, info};
+use crate::burt::{Burt, BurtGang, GenerationStats, optional_to_string};
+use crate::genome::{Genome, parse_genome};
+use crate::island::{IslandSettings, IslandStats};
+use crate::stopping::{StopCondition, StopProgress};
+
+// while training, the UI gets at most one snapshot this often (copying millions of burts isn't free)
+const SNAPSHOT_EVERY: Duration = Duration::from_millis(100);
+
+// how long the generations per second are averaged over
+const THROUGHPUT_EVERY: Duration = Duration::from_millis(500);
+
+/// how many generations without a better average score it takes to count as converged
+pub const DEFAULT_CONVERGE_PATIENCE: u32 = 25;
+
+/// a change to make to the gang on the training thread, the message is shown in the footer (unless it's empty)
+pub type Change = Box<dyn FnOnce(&mut BurtGang) -> Result<String, String> + Send>;
+
+pub enum Command {
+    Resume,
+    // also stops any steps or converging
+    Pause,
+    // train this many generations, even past the last one
+    Step(u32),
+    // train until the average score is 0 or hasn't improved in this many generations, ignoring the last generation
+    Converge(u32),
+    // the time between generations, 0 trains as fast as possible
+    Delay(Duration),
+    Change(Change),
+    Stop,
+}
+
+enum Update {
+    // boxed, a snapshot is much larger than a reply
+    Snapshot(Box<Snapshot>),
+    Reply(Result<String, String>),
+}
+
+/// what the UI can see of the gang, copied by the training thread
+pub struct Snapshot {
+    pub burts: Vec<Burt>,
+    pub range: u32,
+    pub target: u32,
+    pub generations: u32,
+    pub current_generation: u32,
+    pub survival_rate: f32,
+    pub mutation_rate: f32,
+    pub selection: String,
+    pub elite_count: u32,
+    pub schedule: String,
+    pub fitness: String,
+    pub samples_per_eval: u32,
+    pub genome: Box<dyn Genome>,
+    pub average_guess: Option<u32>,
+    pub average_score: Option<u32>,
+    // the stats of every generation. the training thread only sends the ones the UI doesn't have yet,
+    // they go at `history_from` (the UI drops anything it has from there on, i.e. after a reset)
+    pub history: Vec<GenerationStats>,
+    history_from: usize,
+    pub islands: Vec<IslandSettings>,
+    pub island_stats: Vec<IslandStats>,
+    pub migration: String,
+    pub sharing: String,
+    // training in any way, running, stepping or converging
+    pub running: bool,
+    pub delay: Duration,
+    pub generations_per_second: f32,
+}
+
+impl Snapshot {
+    fn of(burt_gang: &BurtGang, history_from: usize, running: bool, delay: Duration, generations_per_second: f32) -> Self {
+        Self {
+            burts: burt_gang.iter().cloned().collect(),
+            range: burt_gang.range,
+            target: burt_gang.target,
+            generations: burt_gang.generations,
+            current_generation: burt_gang.current_generation,
+            survival_rate: burt_gang.survival_rate,
+            mutation_rate: burt_gang.mutation_rate,
+            selection: burt_gang.selection.name(),
+            elite_count: burt_gang.elite_count,
+            schedule: burt_gang.schedule.name(),
+            fitness: burt_gang.fitness.name(),
+            samples_per_eval: burt_gang.samples_per_eval,
+            // genomes can't be cloned, but their names always parse back into them
+            genome: parse_genome(&burt_gang.genome.name()).expect("Failed to copy the genome"),
+            average_guess: burt_gang.average_guess(),
+            average_score: burt_gang.average_score(),
+            history: burt_gang.history()[history_from..].to_vec(),
+            history_from,
+            islands: burt_gang.island_settings(),
+            island_stats: burt_gang.island_stats().to_vec(),
+            migration: burt_gang.migration.name(),
+            sharing: burt_gang.sharing.name(),
+            running,
+            delay,
+            generations_per_second,
+        }
+    }
+
+    pub fn iter(&self) -> core::slice::Iter<'_, Burt> {
+        self.burts.iter()
+    }
+
+    pub fn get(&self, x: usize) -> &Burt {
+        self.burts.get(x).expect("Failed to get burt from snapshot!")
+    }
+
+    pub fn len(&self) -> usize {
+        self.burts.len()
+    }
+
+    pub fn av_guess_display(&self) -> String {
+        optional_to_string(self.average_guess)
+    }
+
+    pub fn av_score_display(&self) -> String {
+        optional_to_string(self.average_score)
+    }
+}
+
+/// the gang once training has stopped, with how long it trained and how the last bit of training ended
+pub struct Finished {
+    pub burt_gang: BurtGang,
+    pub trained_for: Duration,
+    // i.e. "Converged at generation 40", none if training was still going or never stopped by itself
+    pub stop_reason: Option<String>,
+}
+
+/// trains the gang on its own thread so the UI never waits on a generation
+pub struct Trainer {
+    commands: Sender<Command>,
+    updates: Receiver<Update>,
+    handle: JoinHandle<Finished>,
+    snapshot: Snapshot,
+}
+
+impl Trainer {
+    /// moves the gang onto a new training thread, paused
+    pub fn spawn(burt_gang: BurtGang, advanced: bool) -> Self {
+        let snapshot = Snapshot::of(&burt_gang, 0, false, Duration::ZERO, 0.0);
+        let (commands, command_rx) = channel();
+        let (update_tx, updates) = channel();
+        let handle = thread::Builder::new()
+            .name(String::from("training"))
+            .spawn(move || train(burt_gang, advanced, command_rx, update_tx))
+            .expect("Failed to start the training thread");
+
+        Self { commands, updates, handle, snapshot }
+    }
+
+    pub fn send(&self, command: Command) {
+        // the thread only stops when it's told to, so this can't fail while the trainer exists
+        self.commands.send(command).expect("The training thread stopped early");
+    }
+
+    /// changes the gang on the training thread, see `Change`
+    pub fn change<F>(&self, change: F)
+        where F: FnOnce(&mut BurtGang) -> Result<String, String> + Send + 'static {
+        self.send(Command::Change(Box::new(change)));
+    }
+
+    /// takes in everything the training thread has sent, returning the replies to changes
+    pub fn update(&mut self) -> Vec<Result<String, String>> {
+        let mut replies = Vec::new();
+        for update in self.updates.try_iter() {
+            match update {
+                Update::Snapshot(snapshot) => {
+                    let mut snapshot = *snapshot;
+                    let mut history = std::mem::take(&mut self.snapshot.history);
+                    history.truncate(snapshot.history_from);
+                    history.append(&mut snapshot.history);
+                    snapshot.history = history;
+                    self.snapshot = snapshot;
+                }
+                Update::Reply(reply) => replies.push(reply),
+            }
+        }
+        replies
+    }
+
+    /// the latest snapshot of the gang
+    pub fn snapshot(&self) -> &Snapshot {
+        &self.snapshot
+    }
+
+    /// stops training (after the generation being trained) and gives the gang back
+    pub fn stop(self) -> Finished {
+        // the thread is already gone if it panicked, join reports that below
+        let _ = self.commands.send(Command::Stop);
+        self.handle.join().expect("The training thread panicked")
+    }
+}
+
+// save the gang if a checkpoint is due, the log shows how it went
+fn auto_checkpoint(burt_gang: &BurtGang) {
+    match burt_gang.checkpoint() {
+        Some(Ok(())) => info!(target:"MaLB", "Saved checkpoint of generation {} to {}",
+            burt_gang.current_generation, burt_gang.checkpoint_path.display()),
+        Some(Err(e)) => error!(target:"MaLB", "Failed to save checkpoint to {}: {}",
+            burt_gang.checkpoint_path.display(), e),
+        None => {}
+    }
+}
+
+// converging is training until the gang is stagnant (or perfect), the same as the stagnant stop condition
+struct Convergence {
+    stagnant: StopCondition,
+    progress: StopProgress,
+}
+
+impl Convergence {
+    fn new(patience: u32) -> Self {
+        Self { stagnant: StopCondition::Stagnant(patience), progress: StopProgress::default() }
+    }
+
+    // true once the gang has converged
+    fn update(&mut self, burt_gang: &BurtGang) -> bool {
+        if let Some(stats) = burt_gang.history().last() {
+            self.progress.update(stats);
+        }
+        burt_gang.average_score() == Some(0) || self.stagnant.check(&self.progress, Duration::ZERO).is_some()
+    }
+}
+
+// the training thread, runs until it's told to stop or the trainer is dropped
+fn train(mut burt_gang: BurtGang, advanced: bool, commands: Receiver<Command>, updates: Sender<Update>) -> Finished {
+    let mut running = false;
+    let mut steps: u32 = 0;
+    let mut converging: Option<Convergence> = None;
+    let mut delay = Duration::ZERO;
+    let mut last_generation = Instant::now();
+    let mut last_snapshot = Instant::now();
+    // how many generations of history the UI has and the last of them, the first snapshot had all of it
+    let mut sent_history = burt_gang.history().len();
+    let mut last_sent = burt_gang.history().last().map(|stats| stats.generation);
+    // how long the gang has trained for, for the time limit. the clock starts over after waiting for a command,
+    // so paused time doesn't count
+    let mut trained_for = Duration::ZERO;
+    let mut clock = Instant::now();
+    // why training last stopped by itself
+    let mut stop_reason: Option<String> = None;
+
+    // generations per second
+    let mut throughput = 0.0;
+    let mut throughput_start = Instant::now();
+    let mut throughput_count: u32 = 0;
+
+    loop {
+        // only wait for a command when there's no training to do, and otherwise only until the next generation is due
+        let training = steps > 0 || converging.is_some() || (running && burt_gang.current_generation < burt_gang.generations);
+        let command = if training {
+            match commands.recv_timeout(delay.saturating_sub(last_generation.elapsed())) {
+                Ok(command) => Some(command),
+                Err(RecvTimeoutError::Timeout) => None,
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        } else {
+            let command = match commands.recv() {
+                Ok(command) => command,
+                Err(_) => break,
+            };
+            clock = Instant::now();
+            Some(command)
+        };
+
+        let commanded = command.is_some();
+        match command {
+            Some(Command::Resume) => running = true,
+            Some(Command::Pause) => {
+                running = false;
+                steps = 0;
+                converging = None;
+            }
+            Some(Command::Step(n)) => {
+                info!(target:"MalB", "User forced run of {} training generations: {}/{}",
+                    n, burt_gang.current_generation, burt_gang.generations);
+                steps += n;
+            }
+            Some(Command::Converge(patience)) => {
+                info!(target:"MaLB", "Training until the average score hasn't improved in {} generations", patience);
+                converging = Some(Convergence::new(patience));
+            }
+            Some(Command::Delay(new_delay)) => delay = new_delay,
+            Some(Command::Change(change)) => {
+                let reply = change(&mut burt_gang);
+                if updates.send(Update::Reply(reply)).is_err() {
+                    break;
+                }
+            }
+            Some(Command::Stop) => break,
+            None => {
+                burt_gang.train(advanced);
+                auto_checkpoint(&burt_gang);
+                last_generation = Instant::now();
+                trained_for += clock.elapsed();
+                clock = Instant::now();
+                throughput_count += 1;
+                steps = steps.saturating_sub(1);
+                stop_reason = None;
+
+                // stepping and converging say how long to train themselves, the stop conditions are for running
+                if running && steps == 0 && converging.is_none() {
+                    let stopped = if let Some(reason) = burt_gang.stop_reason(trained_for) {
+                        running = false;
+                        Some(format!("Stopped at generation {}/{}: {}", burt_gang.current_generation, burt_gang.generations, reason))
+                    } else if burt_gang.current_generation == burt_gang.generations {
+                        Some(format!("Training complete: trained all {} generations", burt_gang.generations))
+                    } else {
+                        None
+                    };
+                    if let Some(stopped) = stopped {
+                        info!(target:"MaLB", "{} ({:.1}s of training)", stopped, trained_for.as_secs_f32());
+                        stop_reason = Some(stopped.clone());
+                        if updates.send(Update::Reply(Ok(stopped))).is_err() {
+                            break;
+                        }
+                    }
+                }
+
+                if let Some(convergence) = &mut converging {
+                    if convergence.update(&burt_gang) {
+                        converging = None;
+                        info!(target:"MaLB", "Converged at generation {}", burt_gang.current_generation);
+                        stop_reason = Some(format!("Converged at generation {}", burt_gang.current_generation));
+                        let reply = Ok(format!("Converged at generation {}", burt_gang.current_generation));
+                        if updates.send(Update::Reply(reply)).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        if throughput_start.elapsed() >= THROUGHPUT_EVERY {
+            throughput = throughput_count as f32 / throughput_start.elapsed().as_secs_f32();
+            throughput_start = Instant::now();
+            throughput_count = 0;
+        }
+
+        // always show anything the user did and the last generation before waiting for commands straight away
+        let training = steps > 0 || converging.is_some() || (running && burt_gang.current_generation < burt_gang.generations);
+        if commanded || !training || last_snapshot.elapsed() >= SNAPSHOT_EVERY {
+            // only send the history the UI doesn't have, all of it if it started over since (a reset or a load)
+            let history = burt_gang.history();
+            let history_from = if history.get(..sent_history).is_some_and(|sent| sent.last().map(|stats| stats.generation) == last_sent) {
+                sent_history
+            } else {
+                0
+            };
+            sent_history = history.len();
+            last_sent = history.last().map(|stats| stats.generation);
+            // the throughput isn't updated while waiting for commands, so don't show a stale one
+            let snapshot = Snapshot::of(&burt_gang, history_from, running || steps > 0 || converging.is_some(),
+                                        delay, if training { throughput } else { 0.0 });
+            if updates.send(Update::Snapshot(Box::new(snapshot))).is_err() {
+                break;
+            }
+            last_snapshot = Instant::now();
+        }
+    }
+
+    Finished { burt_gang, trained_for, stop_reason }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::stopping::parse_stop_conditions;
+    use super::*;
+
+    // waits for the training thread to send a snapshot that passes `check`
+    fn wait_for(trainer: &mut Trainer, check: impl Fn(&Snapshot) -> bool) {
+        let start = Instant::now();
+        while !check(trainer.snapshot()) {
+            assert!(start.elapsed() < Duration::from_secs(10), "The training thread never caught up");
+            trainer.update();
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn trains_in_the_background() {
+        let mut burt_gang = BurtGang::new(100, 7, 20, 0.25, 0.01, 8);
+        burt_gang.populate(50, false);
+        let mut trainer = Trainer::spawn(burt_gang, false);
+
+        trainer.send(Command::Step(3));
+        wait_for(&mut trainer, |s| s.current_generation == 3 && !s.running);
+
+        trainer.change(|burt_gang| {
+            burt_gang.target = 30;
+            Ok(String::from("changed"))
+        });
+        trainer.send(Command::Resume);
+        wait_for(&mut trainer, |s| s.current_generation == 20 && s.running);
+        assert_eq!(trainer.snapshot().target, 30);
+        // the history came over a few generations at a time, but the UI has all of it
+        let generations: Vec<u32> = trainer.snapshot().history.iter().map(|stats| stats.generation).collect();
+        assert_eq!(generations, (1..=20).collect::<Vec<u32>>());
+
+        // a reset gang starts its history over
+        trainer.send(Command::Pause);
+        trainer.change(|burt_gang| {
+            let mut fresh = burt_gang.settings_copy();
+            fresh.populate(50, false);
+            *burt_gang = fresh;
+            Ok(String::new())
+        });
+        trainer.send(Command::Step(2));
+        wait_for(&mut trainer, |s| s.current_generation == 2 && !s.running);
+        assert_eq!(trainer.snapshot().history.len(), 2);
+
+        let burt_gang = trainer.stop().burt_gang;
+        assert_eq!(burt_gang.current_generation, 2);
+    }
+
+    #[test]
+    fn stops_when_a_condition_is_met() {
+        let mut burt_gang = BurtGang::new(100, 7, 1000, 0.25, 0.01, 8);
+        burt_gang.populate(50, false);
+        burt_gang.stop_when = parse_stop_conditions("perfect:3").unwrap();
+        let mut trainer = Trainer::spawn(burt_gang, false);
+
+        trainer.send(Command::Resume);
+        let start = Instant::now();
+        let stopped = loop {
+            assert!(start.elapsed() < Duration::from_secs(10), "Training never stopped");
+            if let Some(reply) = trainer.update().pop() {
+                break reply;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert!(stopped.unwrap().contains("the best score has been 0 for 3 generations"));
+        wait_for(&mut trainer, |s| !s.running);
+        let finished = trainer.stop();
+        assert!(finished.burt_gang.current_generation < 1000);
+        assert!(finished.stop_reason.unwrap().starts_with("Stopped at generation"));
+    }
+
+    #[test]
+    fn converges_past_the_last_generation() {
+        let mut burt_gang = BurtGang::new(100, 7, 1, 0.25, 0.01, 8);
+        burt_gang.populate(50, false);
+        let mut trainer = Trainer::spawn(burt_gang, false);
+
+        trainer.send(Command::Converge(5));
+        let start = Instant::now();
+        let converged = loop {
+            assert!(start.elapsed() < Duration::from_secs(10), "The gang never converged");
+            if let Some(reply) = trainer.update().pop() {
+                break reply;
+            }
+            thread::sleep(Duration::from_millis(1));
+        };
+        assert!(converged.unwrap().starts_with("Converged at generation"));
+        assert!(trainer.stop().burt_gang.current_generation > 5);
+    }
+}
@@ -3,9 +3,11 @@ use tui::backend::CrosstermBackend;
 use tui::Frame;
 use tui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use tui::style::{Color, Modifier, Style};
+use tui::symbols::Marker;
 use tui::text::{Span, Spans};
-use tui::widgets::{Block, Borders, BorderType, List, ListItem, ListState, Paragraph, Row, Table};
-use crate::BurtGang;
+use tui::widgets::{Axis, BarChart, Block, Borders, BorderType, Chart, Dataset, GraphType, List, ListItem, ListState, Paragraph, Row, Sparkline, Table};
+use crate::burt::GenerationStats;
+use crate::trainer::Snapshot;
 
 pub enum Event<I> {
     Input(I),
@@ -16,6 +18,9 @@ pub enum Event<I> {
 pub enum MenuItem {
     Home,
     Burts,
+    Stats,
+    Histogram,
+    Islands,
     Log,
 }
 
@@ -24,12 +29,15 @@ impl From<MenuItem> for usize {
         match input {
             MenuItem::Home => 0,
             MenuItem::Burts => 1,
-            MenuItem::Log => 2,
+            MenuItem::Stats => 2,
+            MenuItem::Histogram => 3,
+            MenuItem::Islands => 4,
+            MenuItem::Log => 5,
         }
     }
 }
 
-pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>, burt_gang: &mut BurtGang) {
+pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &[Rect], burt_gang: &Snapshot) {
     let home_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints(
@@ -48,8 +56,8 @@ pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>,
             Style::default().fg(Color::LightYellow),
         )]),
         Spans::from(vec![Span::raw("")]),
-        Spans::from(vec![Span::raw("Press 'h' for Home, 'b' for Burts, 'l' for Logs, 's' to start/stop,")]),
-        Spans::from(vec![Span::raw("'r' to reset, 't' to run a command, and 'q' for Quit")]),
+        Spans::from(vec![Span::raw("Press 'h' for Home, 'b' for Burts, 'g' for Graphs, 'd' for Distribution, 'i' for Islands, 'l' for Logs, 's' to start/stop,")]),
+        Spans::from(vec![Span::raw("'r' to reset, 'e' to step, '+'/'-' to speed up/slow down, 't' to run a command, and 'q' for Quit")]),
     ])
         .alignment(Alignment::Center)
         .block(
@@ -61,14 +69,25 @@ pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>,
         );
 
     let home_details = Table::new(vec![Row::new(vec![
-        Span::raw(format!("{}", burt_gang.target)), // target
+        Span::raw(if burt_gang.schedule == "fixed" {
+            format!("{}", burt_gang.target)
+        } else {
+            format!("{} ({})", burt_gang.target, burt_gang.schedule)
+        }), // target
         Span::raw(format!("{}", burt_gang.range)), // range
         Span::raw(format!("{} / {}", burt_gang.current_generation, burt_gang.generations)), // generation
         Span::raw(format!("{}", burt_gang.survival_rate)), // survival rate
         Span::raw(format!("{}", burt_gang.mutation_rate)), // mutation rate
+        Span::raw(burt_gang.selection.clone()), // selection strategy
+        Span::raw(format!("{}", burt_gang.elite_count)), // elite count
         Span::raw(format!("{}", burt_gang.len())), // burt count
-        Span::raw(format!("{}", burt_gang.av_guess_display())), // average guess
-        Span::raw(format!("{}", burt_gang.av_score_display())), // average score
+        Span::raw(burt_gang.av_guess_display()), // average guess
+        Span::raw(if burt_gang.fitness == "absolute" {
+            burt_gang.av_score_display()
+        } else {
+            format!("{} ({})", burt_gang.av_score_display(), burt_gang.fitness)
+        }), // average score
+        Span::raw(format!("{:.1}", burt_gang.generations_per_second)), // throughput
     ])])
         .header(Row::new(vec![
             Span::styled(
@@ -91,6 +110,14 @@ pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>,
                 "Mutation Rate",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
+            Span::styled(
+                "Selection",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                "Elites",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
             Span::styled(
                 "Burt Count",
                 Style::default().add_modifier(Modifier::BOLD),
@@ -103,6 +130,10 @@ pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>,
                 "Average Score",
                 Style::default().add_modifier(Modifier::BOLD),
             ),
+            Span::styled(
+                "Gens/s",
+                Style::default().add_modifier(Modifier::BOLD),
+            ),
         ]))
         .block(
             Block::default()
@@ -111,21 +142,12 @@ pub fn draw_home(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>,
                 .title("Details")
                 .border_type(BorderType::Plain),
         )
-        .widths(&[
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-            Constraint::Percentage(10),
-        ]);
+        .widths(&[Constraint::Ratio(1, 11); 11]);
     rect.render_widget(home, home_chunks[0]);
     rect.render_widget(home_details, home_chunks[1]);
 }
 
-pub fn draw_burts(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>, burt_gang: &BurtGang, burt_list_state: &mut ListState) {
+pub fn draw_burts(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &[Rect], burt_gang: &Snapshot, burt_list_state: &mut ListState) {
     let burts_chunks = Layout::default()
         .direction(Direction::Horizontal)
         .constraints(
@@ -164,45 +186,341 @@ pub fn draw_burts(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &Vec<Rect>
             .add_modifier(Modifier::BOLD),
     );
 
-    let burt_detail = Table::new(vec![
-        Row::new(vec![
-            //Span::raw(format!("Burt #{}", selected_burt.get_id())), // id - moved to title
-            Span::raw(format!("{}", selected_burt.get_score_display())), // score
-            Span::raw(format!("{}", selected_burt.get_guess_display())), // guess
-            Span::raw(format!("{}", selected_burt.get_mu())), // mu
-            Span::raw(format!("{}", selected_burt.get_sigma())), // sigma
-        ])])
-        .header(Row::new(vec![
-            Span::styled(
-                "Score",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Guess",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Mu",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-            Span::styled(
-                "Sigma",
-                Style::default().add_modifier(Modifier::BOLD),
-            ),
-        ]))
+    let mut details = vec![
+        //Span::raw(format!("Burt #{}", selected_burt.get_id())), // id - moved to title
+        Span::raw(selected_burt.get_score_display()), // score
+        Span::raw(selected_burt.get_best_score_display()), // best score
+        Span::raw(selected_burt.get_variance_display()), // score variance
+        Span::raw(selected_burt.get_guess_display()), // guess
+    ];
+    // then a column for every gene
+    details.extend(selected_burt.genes().iter().map(|gene| Span::raw(format!("{}", gene))));
+
+    let mut header = vec![
+        Span::styled(
+            "Mean Score",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "Best Score",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "Variance",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(
+            "Guess",
+            Style::default().add_modifier(Modifier::BOLD),
+        ),
+    ];
+    header.extend(burt_gang.genome.gene_names().iter().map(|name| Span::styled(
+        title_case(name),
+        Style::default().add_modifier(Modifier::BOLD),
+    )));
+
+    // the genes share what the scores and guess leave over
+    let genes = burt_gang.genome.gene_names().len().max(1) as u16;
+    let mut widths = vec![Constraint::Percentage(10); 4];
+    widths.extend(vec![Constraint::Percentage(60 / genes); genes as usize]);
+
+    let describe = burt_gang.genome.describe(selected_burt.genes(), burt_gang.range as f32);
+    let burt_detail = Table::new(vec![Row::new(details)])
+        .header(Row::new(header))
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::White))
-                .title(format!("Burt #{}", selected_burt.get_id()))
+                .title(if burt_gang.samples_per_eval == 1 {
+                    format!("Burt #{}: {}", selected_burt.get_id(), describe)
+                } else {
+                    format!("Burt #{} ({} evaluations): {}", selected_burt.get_id(), burt_gang.samples_per_eval, describe)
+                })
                 .border_type(BorderType::Plain),
         )
-        .widths(&[
-            Constraint::Percentage(10),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
-            Constraint::Percentage(20),
-        ]);
+        .widths(&widths);
     rect.render_stateful_widget(burts_list_left, burts_chunks[0], burt_list_state);
     rect.render_widget(burt_detail, burts_chunks[1]);
-}
\ No newline at end of file
+}
+
+// "mu" as "Mu", for the names of genes
+fn title_case(name: &str) -> String {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+// a line of the chart, one point per generation
+fn series(history: &[&GenerationStats], value: impl Fn(&GenerationStats) -> f32) -> Vec<(f64, f64)> {
+    history.iter().map(|stats| (stats.generation as f64, value(stats) as f64)).collect()
+}
+
+// the bottom, middle and top of an axis
+fn axis_labels(bounds: [f64; 2]) -> Vec<Span<'static>> {
+    vec![
+        Span::raw(format!("{:.0}", bounds[0])),
+        Span::raw(format!("{:.0}", (bounds[0] + bounds[1]) / 2.0)),
+        Span::raw(format!("{:.0}", bounds[1])),
+    ]
+}
+
+fn stats_chart<'a>(title: &'a str, datasets: Vec<Dataset<'a>>, x_bounds: [f64; 2], y_top: f64) -> Chart<'a> {
+    let y_bounds = [0.0, y_top.max(1.0)];
+    Chart::new(datasets)
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .x_axis(Axis::default()
+            .title("Generation")
+            .style(Style::default().fg(Color::Gray))
+            .bounds(x_bounds)
+            .labels(axis_labels(x_bounds)))
+        .y_axis(Axis::default()
+            .style(Style::default().fg(Color::Gray))
+            .bounds(y_bounds)
+            .labels(axis_labels(y_bounds)))
+        // show the legend unless the chart is really small
+        .hidden_legend_constraints((Constraint::Ratio(1, 4), Constraint::Ratio(1, 2)))
+}
+
+fn line<'a>(name: &'a str, color: Color, data: &'a [(f64, f64)]) -> Dataset<'a> {
+    Dataset::default()
+        .name(name)
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(data)
+}
+
+// the colors of the genes' lines, yellow is the mean guess
+const GENE_COLORS: [Color; 5] = [Color::Cyan, Color::Magenta, Color::Green, Color::Red, Color::Blue];
+
+pub fn draw_stats(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &[Rect], burt_gang: &Snapshot) {
+    let stats_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [Constraint::Percentage(45), Constraint::Percentage(35), Constraint::Percentage(20)].as_ref(),
+        )
+        .split(chunks[1]);
+
+    // only plot about as many generations as there is room for (braille has 2 dots per column)
+    let step = (burt_gang.history.len() / (chunks[1].width as usize * 2)).max(1);
+    let history: Vec<&GenerationStats> = burt_gang.history.iter().step_by(step).collect();
+    let x_bounds = match (history.first(), burt_gang.history.last()) {
+        (Some(first), Some(last)) => [first.generation as f64, (last.generation as f64).max(first.generation as f64 + 1.0)],
+        _ => [0.0, 1.0],
+    };
+
+    let min_score = series(&history, |stats| stats.min_score as f32);
+    let median_score = series(&history, |stats| stats.median_score as f32);
+    let mean_score = series(&history, |stats| stats.mean_score);
+    let max_score = series(&history, |stats| stats.max_score as f32);
+    let top_score = history.iter().map(|stats| stats.max_score).max().unwrap_or_default();
+    let scores = stats_chart("Score", vec![
+        line("Min", Color::Green, &min_score),
+        line("Median", Color::Yellow, &median_score),
+        line("Mean", Color::Cyan, &mean_score),
+        line("Max", Color::Red, &max_score),
+    ], x_bounds, top_score as f64);
+
+    let mean_guess = series(&history, |stats| stats.mean_guess);
+    // one line per gene, a changed genome starts the lines over
+    let gene_names = burt_gang.genome.gene_names();
+    let gene_labels: Vec<String> = gene_names.iter().map(|name| format!("{} Std Dev", title_case(name))).collect();
+    let gene_stddevs: Vec<Vec<(f64, f64)>> = (0..gene_names.len())
+        .map(|x| series(&history, |stats| stats.gene_stddevs.get(x).copied().unwrap_or_default()))
+        .collect();
+    let rms_distance = series(&history, |stats| stats.rms_distance);
+    let top_gene = history.iter()
+        .flat_map(|stats| stats.gene_stddevs.iter().copied().chain([stats.mean_guess, stats.rms_distance]))
+        .fold(burt_gang.target as f32, f32::max);
+    let mut gene_lines = vec![line("Mean Guess", Color::Yellow, &mean_guess)];
+    for (x, (label, data)) in gene_labels.iter().zip(&gene_stddevs).enumerate() {
+        gene_lines.push(line(label, GENE_COLORS[x % GENE_COLORS.len()], data));
+    }
+    gene_lines.push(line("RMS Distance", Color::White, &rms_distance));
+    let genes = stats_chart("Genes", gene_lines, x_bounds, top_gene as f64);
+
+    let sparkline_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(
+            [Constraint::Percentage(50), Constraint::Percentage(50)].as_ref(),
+        )
+        .split(stats_chunks[2]);
+
+    // the sparklines draw one bar per column, so they get the latest generations that fit
+    let width = sparkline_chunks[0].width.saturating_sub(2) as usize;
+    let skip = burt_gang.history.len().saturating_sub(width);
+    let perfect: Vec<u64> = burt_gang.history[skip..].iter().map(|stats| stats.perfect_count as u64).collect();
+    let unique: Vec<u64> = burt_gang.history[skip..].iter().map(|stats| stats.unique_genomes as u64).collect();
+    let perfect_burts = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(format!("Perfect Burts (latest {}, max {})", perfect.len(), perfect.iter().max().unwrap_or(&0)))
+                .border_type(BorderType::Plain),
+        )
+        .style(Style::default().fg(Color::LightYellow))
+        .data(&perfect);
+    let sharing = if burt_gang.sharing == "none" { String::new() } else { format!(", sharing {}", burt_gang.sharing) };
+    let unique_genomes = Sparkline::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(format!("Unique Genomes (latest {}, now {}{})", unique.len(), unique.last().unwrap_or(&0), sharing))
+                .border_type(BorderType::Plain),
+        )
+        .style(Style::default().fg(Color::LightGreen))
+        .data(&unique);
+
+    rect.render_widget(scores, stats_chunks[0]);
+    rect.render_widget(genes, stats_chunks[1]);
+    rect.render_widget(perfect_burts, sparkline_chunks[0]);
+    rect.render_widget(unique_genomes, sparkline_chunks[1]);
+}
+
+// counts the values into at most `buckets` bars that each cover `bucket_size` values of 0..=range,
+// labelling the bar the target falls in with a caret. also returns which bar that is
+fn histogram(values: &[u32], range: u32, target: u32, buckets: usize) -> (Vec<String>, Vec<u64>, u32, usize) {
+    let bucket_size = (range + 1).div_ceil(buckets.max(1) as u32);
+    let buckets = (range + 1).div_ceil(bucket_size) as usize;
+    let mut counts = vec![0u64; buckets];
+    for value in values {
+        counts[((value / bucket_size) as usize).min(buckets - 1)] += 1;
+    }
+    let target_bucket = ((target / bucket_size) as usize).min(buckets - 1);
+    let labels = (0..buckets).map(|x| String::from(if x == target_bucket { "^" } else { "" })).collect();
+    (labels, counts, bucket_size, target_bucket)
+}
+
+fn histogram_chart<'a>(title: String, data: &'a [(&'a str, u64)], bar_width: u16) -> BarChart<'a> {
+    BarChart::default()
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .data(data)
+        .bar_width(bar_width)
+        .bar_gap(0)
+        .bar_style(Style::default().fg(Color::LightCyan))
+        .value_style(Style::default().fg(Color::Black).bg(Color::LightCyan))
+        .label_style(Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD))
+}
+
+// draws the target's bar over the chart in `area` again, in its own colour. a bar chart only has one bar style,
+// so it is a second chart of just that bar, scaled to the same tallest bar
+fn draw_target_bar(rect: &mut Frame<CrosstermBackend<Stdout>>, area: Rect, data: &[(&str, u64)], target_bucket: usize, bar_width: u16) {
+    // the bar is left out when it doesn't fit inside the chart's border, like the chart does
+    let left = target_bucket as u16 * bar_width;
+    if area.width < 2 || area.height < 2 || left + bar_width > area.width - 2 {
+        return;
+    }
+    let bar_area = Rect::new(area.x + 1 + left, area.y + 1, bar_width, area.height - 2);
+    let max = data.iter().map(|(_, count)| *count).max().unwrap_or_default();
+    let target_bar = BarChart::default()
+        .data(&data[target_bucket..=target_bucket])
+        .max(max)
+        .bar_width(bar_width)
+        .bar_gap(0)
+        .bar_style(Style::default().fg(Color::LightRed))
+        .value_style(Style::default().fg(Color::Black).bg(Color::LightRed))
+        .label_style(Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD));
+    rect.render_widget(target_bar, bar_area);
+}
+
+pub fn draw_histogram(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &[Rect], burt_gang: &Snapshot) {
+    let histogram_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(
+            [Constraint::Percentage(50), Constraint::Percentage(50)].as_ref(),
+        )
+        .split(chunks[1]);
+
+    // one bar per column at most, so a large range is squeezed into buckets instead of running off the screen
+    let width = histogram_chunks[0].width.saturating_sub(2).max(1) as usize;
+    let buckets = width.min(burt_gang.range as usize + 1);
+
+    let guesses: Vec<u32> = burt_gang.iter().filter_map(|b| b.get_guess()).collect();
+    // the first gene, which is where the guesses are centred for most genomes
+    let mus: Vec<u32> = burt_gang.iter().map(|b| b.genes()[0].round() as u32).collect();
+
+    let (guess_labels, guess_counts, bucket_size, target_bucket) = histogram(&guesses, burt_gang.range, burt_gang.target, buckets);
+    let (mu_labels, mu_counts, ..) = histogram(&mus, burt_gang.range, burt_gang.target, buckets);
+    let bar_width = (width / guess_counts.len()).max(1) as u16;
+
+    let guess_data: Vec<(&str, u64)> = guess_labels.iter().map(|l| l.as_str()).zip(guess_counts).collect();
+    let mu_data: Vec<(&str, u64)> = mu_labels.iter().map(|l| l.as_str()).zip(mu_counts).collect();
+
+    let bars = if bucket_size == 1 {
+        String::from("one bar per value")
+    } else {
+        format!("{} values per bar", bucket_size)
+    };
+    let guess_title = if guesses.is_empty() {
+        String::from("Guesses (none yet)")
+    } else {
+        format!("Guesses of generation {} ({}, ^ in red is the target {})", burt_gang.current_generation, bars, burt_gang.target)
+    };
+    let mu_title = format!("{} ({}, ^ in red is the target {})", title_case(burt_gang.genome.gene_names()[0]), bars, burt_gang.target);
+
+    rect.render_widget(histogram_chart(guess_title, &guess_data, bar_width), histogram_chunks[0]);
+    draw_target_bar(rect, histogram_chunks[0], &guess_data, target_bucket, bar_width);
+    rect.render_widget(histogram_chart(mu_title, &mu_data, bar_width), histogram_chunks[1]);
+    draw_target_bar(rect, histogram_chunks[1], &mu_data, target_bucket, bar_width);
+}
+
+pub fn draw_islands(rect: &mut Frame<CrosstermBackend<Stdout>>, chunks: &[Rect], burt_gang: &Snapshot) {
+    // the stats are from the last generation, so they're missing until the islands have trained
+    let stats = if burt_gang.island_stats.len() == burt_gang.islands.len() { burt_gang.island_stats.as_slice() } else { &[] };
+    let rows: Vec<Row> = burt_gang.islands.iter().enumerate().map(|(x, island)| {
+        let island_stats = stats.get(x);
+        let stat = |value: Option<String>| value.unwrap_or_else(|| String::from("?"));
+        Row::new(vec![
+            Span::raw(format!("{}", x)),
+            Span::raw(format!("{}", island.burts)),
+            Span::raw(format!("{}", island.survival_rate)),
+            Span::raw(format!("{}", island.mutation_rate)),
+            Span::raw(island.selection.clone()),
+            Span::raw(stat(island_stats.and_then(|s| s.average_guess).map(|v| v.to_string()))),
+            Span::raw(stat(island_stats.and_then(|s| s.average_score).map(|v| v.to_string()))),
+            Span::raw(stat(island_stats.and_then(|s| s.best_score).map(|v| v.to_string()))),
+            Span::raw(stat(island_stats.map(|s| s.perfect_count.to_string()))),
+            Span::raw(stat(island_stats.map(|s| format!("{:.2}", s.gene_stddev)))),
+        ])
+    }).collect();
+
+    let gene_stddev = format!("{} Std Dev", title_case(burt_gang.genome.gene_names()[0]));
+    let header: Vec<Span> = ["Island", "Burts", "Survival Rate", "Mutation Rate", "Selection", "Average Guess",
+        "Average Score", "Best Score", "Perfect", gene_stddev.as_str()]
+        .into_iter()
+        .map(|title| Span::styled(title, Style::default().add_modifier(Modifier::BOLD)))
+        .collect();
+
+    let title = if burt_gang.migration == "none" {
+        format!("Islands ({} of them, no migration)", burt_gang.islands.len())
+    } else {
+        format!("Islands ({} of them, migration: {})", burt_gang.islands.len(), burt_gang.migration)
+    };
+    let islands = Table::new(rows)
+        .header(Row::new(header))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::White))
+                .title(title)
+                .border_type(BorderType::Plain),
+        )
+        .widths(&[Constraint::Ratio(1, 10); 10]);
+    rect.render_widget(islands, chunks[1]);
+}